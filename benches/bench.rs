@@ -1,9 +1,16 @@
 use bencher::{benchmark_group, benchmark_main, black_box, Bencher};
 
-use psf2::Font;
+use psf2::{Font, UnicodeIndex};
 
 benchmark_main!(benches);
-benchmark_group!(benches, rasterize);
+benchmark_group!(
+    benches,
+    rasterize,
+    blit,
+    blit_bytes,
+    lookup_scan,
+    lookup_indexed
+);
 
 const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
 
@@ -22,3 +29,47 @@ fn rasterize(b: &mut Bencher) {
         black_box(buf);
     });
 }
+
+fn blit(b: &mut Bencher) {
+    let font = Font::new(FONT).unwrap();
+    let glyph = font.get_ascii(b'A').unwrap();
+    let mut buf = [0u32; 6 * 12];
+    b.iter(|| {
+        glyph.blit(&mut buf, 6, u32::MAX, None);
+        black_box(buf);
+    });
+}
+
+fn blit_bytes(b: &mut Bencher) {
+    let font = Font::new(FONT).unwrap();
+    let glyph = font.get_ascii(b'A').unwrap();
+    let mut buf = [0u8; 6 * 12 * 3];
+    b.iter(|| {
+        glyph.blit_bytes(&mut buf, 6 * 3, [0xff; 3], Some([0; 3]));
+        black_box(buf);
+    });
+}
+
+/// Characters spread across the Unicode table
+const TEXT: &str = "AzÅ≫⊙Ⓩ¤~";
+
+fn lookup_scan(b: &mut Bencher) {
+    let font = Font::new(FONT).unwrap();
+    b.iter(|| {
+        for c in black_box(TEXT).chars() {
+            black_box(font.get_unicode(c));
+        }
+    });
+}
+
+fn lookup_indexed(b: &mut Bencher) {
+    let font = Font::new(FONT).unwrap();
+    let mut bmp = vec![0; UnicodeIndex::BMP_LEN].into_boxed_slice();
+    let bmp = <&mut [u16; UnicodeIndex::BMP_LEN]>::try_from(&mut bmp[..]).unwrap();
+    let index = UnicodeIndex::new_in(&font, bmp, &mut []).unwrap();
+    b.iter(|| {
+        for c in black_box(TEXT).chars() {
+            black_box(index.get(c).and_then(|i| font.get_index(i)));
+        }
+    });
+}
@@ -1,6 +1,6 @@
 use std::{env, fs};
 
-use psf2::Font;
+use psf2::{Canvas, Fallback, Font, Format, RenderOptions};
 
 fn main() {
     let args = env::args().collect::<Vec<_>>();
@@ -10,26 +10,27 @@ fn main() {
     let data = fs::read(&args[1]).unwrap();
     let font = Font::new(data).unwrap();
     let text = match args.get(2) {
-        Some(x) => &x,
+        Some(x) => x,
         None => "demo",
     };
-    for c in text.chars() {
-        let glyph = match font.get_unicode(c) {
-            Some(x) => x,
-            None => {
-                eprintln!("missing glyph: {}", c);
-                continue;
-            }
-        };
-        for row in glyph {
-            for column in row {
-                let x = match column {
-                    true => '█',
-                    false => ' ',
-                };
-                print!("{}", x);
-            }
-            println!();
+    let options = RenderOptions {
+        letter_spacing: 1,
+        ..RenderOptions::default()
+    };
+    let (width, height) = font.measure_str(text, &options);
+    let mut buf = vec![0; Format::Gray8.buffer_len(width, height)];
+    let mut canvas = Canvas::new(&mut buf, Format::Gray8, width, height).unwrap();
+    let fallback = Fallback::new(&font);
+    let mut scratch = vec![0; fallback.buffer_len()];
+    fallback.render_str(text, &mut canvas, &options, &mut scratch);
+    for row in buf.chunks(width.max(1) as usize) {
+        for &pixel in row {
+            let x = match pixel {
+                0 => ' ',
+                _ => '█',
+            };
+            print!("{}", x);
         }
+        println!();
     }
 }
@@ -0,0 +1,109 @@
+//! Substitute glyphs for characters a font lacks
+
+use crate::{Canvas, Font, Glyph, RenderOptions};
+
+/// A font which resolves characters it lacks through a chain of fallbacks, so that text drawn
+/// with it never has holes
+///
+/// Each character is looked up in the font's Unicode table, then each replacement character is
+/// tried in turn, and finally a [placeholder glyph](Font::placeholder_glyph) showing the
+/// character's code point is synthesized. Placeholders are written to a caller-supplied buffer
+/// of at least [`buffer_len`](Self::buffer_len) bytes.
+pub struct Fallback<'a, Data> {
+    font: &'a Font<Data>,
+    replacements: &'a [char],
+    placeholder: bool,
+}
+
+// Manual impls to avoid requiring `Data: Clone`
+impl<Data> Clone for Fallback<'_, Data> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Data> Copy for Fallback<'_, Data> {}
+
+impl<'a, Data: AsRef<[u8]>> Fallback<'a, Data> {
+    /// Replacement characters tried by default: U+FFFD REPLACEMENT CHARACTER, then `?`
+    pub const DEFAULT_REPLACEMENTS: &'static [char] = &['\u{fffd}', '?'];
+
+    /// Wrap `font`, with the default replacements and placeholders enabled
+    pub fn new(font: &'a Font<Data>) -> Self {
+        Self {
+            font,
+            replacements: Self::DEFAULT_REPLACEMENTS,
+            placeholder: true,
+        }
+    }
+
+    /// Try `replacements`, in order, for characters the font lacks
+    pub fn with_replacements(mut self, replacements: &'a [char]) -> Self {
+        self.replacements = replacements;
+        self
+    }
+
+    /// Whether to synthesize a placeholder when no replacement is found
+    pub fn with_placeholder(mut self, enabled: bool) -> Self {
+        self.placeholder = enabled;
+        self
+    }
+
+    /// The wrapped font
+    #[inline]
+    pub fn font(&self) -> &'a Font<Data> {
+        self.font
+    }
+
+    /// Number of bytes needed to hold a placeholder glyph
+    #[inline]
+    pub fn buffer_len(&self) -> usize {
+        self.font.glyph_size()
+    }
+
+    /// Get the glyph for `c`, or for the first replacement the font has, or a placeholder
+    ///
+    /// Returns `None` only if the chain is exhausted and placeholders are disabled or `buf` is
+    /// shorter than [`buffer_len`](Self::buffer_len).
+    pub fn get<'b>(&self, c: char, buf: &'b mut [u8]) -> Option<Glyph<'b>>
+    where
+        'a: 'b,
+    {
+        if let Some(glyph) = self.font.get_unicode(c) {
+            return Some(glyph);
+        }
+        if let Some(glyph) = self
+            .replacements
+            .iter()
+            .find_map(|&x| self.font.get_unicode(x))
+        {
+            return Some(glyph);
+        }
+        if !self.placeholder || buf.len() < self.buffer_len() {
+            return None;
+        }
+        Some(self.font.placeholder_glyph(c, buf))
+    }
+
+    /// Draw `text` into `canvas`, as [`Font::render_str`] but resolving missing characters
+    /// through the chain rather than [`RenderOptions::fallback`]
+    ///
+    /// `buf` is scratch space for placeholders, as for [`get`](Self::get).
+    pub fn render_str(
+        &self,
+        text: &str,
+        canvas: &mut Canvas<'_>,
+        options: &RenderOptions,
+        buf: &mut [u8],
+    ) -> (u32, u32) {
+        self.font
+            .layout_str(text, options, |x, y, glyph| match glyph {
+                Ok(glyph) => canvas.draw(glyph, x, y),
+                Err(c) => {
+                    if let Some(glyph) = self.get(c, buf) {
+                        canvas.draw(glyph, x, y);
+                    }
+                }
+            })
+    }
+}
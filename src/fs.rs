@@ -0,0 +1,61 @@
+//! Conveniences for loading fonts from the filesystem
+
+use std::{fmt, io, path::Path, vec::Vec};
+
+use crate::{Font, ParseError};
+
+impl Font<Vec<u8>> {
+    /// Read and parse the font file at `path`
+    ///
+    /// Gzipped fonts must be decompressed first, e.g. with `Font::from_gzip` when the `gzip`
+    /// feature is enabled.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, OpenError> {
+        let data = std::fs::read(path)?;
+        Ok(Font::new(data)?)
+    }
+}
+
+/// Why a font file might fail to load
+#[derive(Debug)]
+pub enum OpenError {
+    /// The file could not be read
+    Io(io::Error),
+    /// The file is not a valid font
+    Parse(ParseError),
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            OpenError::Io(_) => f.write_str("failed to read font"),
+            OpenError::Parse(_) => f.write_str("failed to parse font"),
+        }
+    }
+}
+
+impl std::error::Error for OpenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            OpenError::Io(ref e) => Some(e),
+            OpenError::Parse(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for OpenError {
+    fn from(x: io::Error) -> Self {
+        OpenError::Io(x)
+    }
+}
+
+impl From<ParseError> for OpenError {
+    fn from(x: ParseError) -> Self {
+        OpenError::Parse(x)
+    }
+}
+
+impl From<ParseError> for io::Error {
+    fn from(x: ParseError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, x)
+    }
+}
@@ -0,0 +1,278 @@
+//! Drawing with the text APIs of [`embedded-graphics`](embedded_graphics)
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    pixelcolor::PixelColor,
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+    Pixel,
+};
+
+use crate::{Font, Glyph};
+
+/// Style for drawing text in a PSF font with [`embedded_graphics::text::Text`]
+///
+/// The counterpart of [`MonoTextStyle`](embedded_graphics::mono_font::MonoTextStyle) for PSF
+/// fonts. Decorations and baselines are rows of the glyph cell, counting from the top.
+pub struct PsfTextStyle<'a, Data, C> {
+    /// The font to draw with
+    pub font: &'a Font<Data>,
+    /// Color of filled pixels, or `None` to leave them untouched
+    pub text_color: Option<C>,
+    /// Color of empty pixels, or `None` to leave them untouched
+    pub background_color: Option<C>,
+    /// Color of the underline
+    pub underline_color: DecorationColor<C>,
+    /// Color of the strikethrough line
+    pub strikethrough_color: DecorationColor<C>,
+    /// Pixels between adjacent glyphs
+    pub letter_spacing: u32,
+    /// Row on which letters sit, used for [`Baseline::Alphabetic`]
+    pub baseline: u32,
+    /// Row of the underline
+    pub underline: u32,
+    /// Row of the strikethrough line
+    pub strikethrough: u32,
+    /// Character drawn in place of those the font lacks, or `None` to leave a blank space
+    pub fallback: Option<char>,
+}
+
+// Manual impls to avoid requiring `Data: Clone`
+impl<Data, C: Copy> Clone for PsfTextStyle<'_, Data, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Data, C: Copy> Copy for PsfTextStyle<'_, Data, C> {}
+
+impl<'a, Data: AsRef<[u8]>, C: PixelColor> PsfTextStyle<'a, Data, C> {
+    /// Draw `font` in `text_color`, without background or decorations
+    ///
+    /// The baseline is taken to be the lowest filled row of the font's `H`, if any, and the
+    /// underline is drawn just beneath it.
+    pub fn new(font: &'a Font<Data>, text_color: C) -> Self {
+        let height = font.height();
+        let baseline = font
+            .get_unicode('H')
+            .and_then(|mut glyph| glyph.rposition(|mut row| row.any(|x| x)))
+            .map_or(height - 1, |x| x as u32);
+        Self {
+            font,
+            text_color: Some(text_color),
+            background_color: None,
+            underline_color: DecorationColor::None,
+            strikethrough_color: DecorationColor::None,
+            letter_spacing: 0,
+            baseline,
+            underline: (baseline + 1).min(height - 1),
+            strikethrough: (height - 1) / 2,
+            fallback: Some('?'),
+        }
+    }
+
+    /// Rows between the top of a glyph and `baseline`
+    fn baseline_offset(&self, baseline: Baseline) -> i32 {
+        let height = self.font.height();
+        (match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => height - 1,
+            Baseline::Middle => (height - 1) / 2,
+            Baseline::Alphabetic => self.baseline,
+        }) as i32
+    }
+
+    /// Width in pixels of `text`
+    fn width(&self, text: &str) -> u32 {
+        let count = self.font.glyphs_for_str(text).count() as u32;
+        let advance = self.font.width().saturating_add(self.letter_spacing);
+        match count {
+            0 => 0,
+            _ => (count - 1)
+                .saturating_mul(advance)
+                .saturating_add(self.font.width()),
+        }
+    }
+
+    /// Draw a glyph, or just the background if `glyph` is `None`, with its top-left corner at
+    /// `position`
+    fn draw_glyph<D>(
+        &self,
+        glyph: Option<Glyph<'_>>,
+        position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let cell = Rectangle::new(position, Size::new(self.font.width(), self.font.height()));
+        let Some(glyph) = glyph else {
+            return match self.background_color {
+                Some(bg) => target.fill_solid(&cell, bg),
+                None => Ok(()),
+            };
+        };
+        let pixels = glyph.enumerate().flat_map(|(y, row)| {
+            row.enumerate()
+                .map(move |(x, filled)| (Point::new(x as i32, y as i32), filled))
+        });
+        match (self.text_color, self.background_color) {
+            (Some(fg), Some(bg)) => target.fill_contiguous(
+                &cell,
+                pixels.map(|(_, filled)| if filled { fg } else { bg }),
+            ),
+            (Some(fg), None) => target.draw_iter(
+                pixels
+                    .filter(|&(_, filled)| filled)
+                    .map(|(p, _)| Pixel(position + p, fg)),
+            ),
+            (None, Some(bg)) => target.draw_iter(
+                pixels
+                    .filter(|&(_, filled)| !filled)
+                    .map(|(p, _)| Pixel(position + p, bg)),
+            ),
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// Draw the underline and strikethrough of a `width` pixel span with its top-left corner at
+    /// `position`
+    fn draw_decorations<D>(
+        &self,
+        width: u32,
+        position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        for (color, row) in [
+            (self.strikethrough_color, self.strikethrough),
+            (self.underline_color, self.underline),
+        ] {
+            let color = match color {
+                DecorationColor::None => None,
+                DecorationColor::TextColor => self.text_color,
+                DecorationColor::Custom(x) => Some(x),
+            };
+            if let Some(color) = color {
+                let line =
+                    Rectangle::new(position + Point::new(0, row as i32), Size::new(width, 1));
+                target.fill_solid(&line, color)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Data: AsRef<[u8]>, C: PixelColor> TextRenderer for PsfTextStyle<'_, Data, C> {
+    type Color = C;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let offset = Point::new(0, self.baseline_offset(baseline));
+        let top_left = position - offset;
+        let fallback = self.fallback.and_then(|c| self.font.get_unicode(c));
+        let spacing = Size::new(self.letter_spacing, self.font.height());
+        let mut next = top_left;
+        for (i, glyph) in self.font.glyphs_for_str(text).enumerate() {
+            if i > 0 && self.letter_spacing > 0 {
+                if let Some(bg) = self.background_color {
+                    target.fill_solid(&Rectangle::new(next, spacing), bg)?;
+                }
+                next.x = next.x.saturating_add(coordinate(self.letter_spacing));
+            }
+            // Glyphs past the end of the coordinate space can't be drawn
+            let Some(end) = next.x.checked_add(coordinate(self.font.width())) else {
+                next.x = i32::MAX;
+                break;
+            };
+            self.draw_glyph(glyph.ok().or_else(|| fallback.clone()), next, target)?;
+            next.x = end;
+        }
+        if next.x > top_left.x {
+            self.draw_decorations(next.x.abs_diff(top_left.x), top_left, target)?;
+        }
+        Ok(next + offset)
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let offset = Point::new(0, self.baseline_offset(baseline));
+        let top_left = position - offset;
+        if width != 0 {
+            if let Some(bg) = self.background_color {
+                let area = Rectangle::new(top_left, Size::new(width, self.font.height()));
+                target.fill_solid(&area, bg)?;
+            }
+            self.draw_decorations(width, top_left, target)?;
+        }
+        Ok(Point::new(
+            position.x.saturating_add(coordinate(width)),
+            position.y,
+        ))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let top_left = position - Point::new(0, self.baseline_offset(baseline));
+        let width = self.width(text);
+        let mut height = self.font.height();
+        if !self.underline_color.is_none() {
+            height = height.max(self.underline + 1);
+        }
+        TextMetrics {
+            bounding_box: Rectangle::new(top_left, Size::new(width, height)),
+            next_position: Point::new(position.x.saturating_add(coordinate(width)), position.y),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font.height()
+    }
+}
+
+impl<Data, C: PixelColor> CharacterStyle for PsfTextStyle<'_, Data, C> {
+    type Color = C;
+
+    fn set_text_color(&mut self, text_color: Option<C>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<C>) {
+        self.background_color = background_color;
+    }
+
+    fn set_underline_color(&mut self, underline_color: DecorationColor<C>) {
+        self.underline_color = underline_color;
+    }
+
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<C>) {
+        self.strikethrough_color = strikethrough_color;
+    }
+}
+
+/// Convert a distance to a coordinate offset, saturating if it's out of range
+#[inline]
+fn coordinate(x: u32) -> i32 {
+    i32::try_from(x).unwrap_or(i32::MAX)
+}
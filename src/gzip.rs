@@ -0,0 +1,177 @@
+//! Decompression of gzipped fonts, e.g. `.psf.gz` files from `/usr/share/consolefonts`
+//!
+//! Decompression writes into a caller-supplied buffer and works without `alloc`. With the `alloc`
+//! feature, [`Font::from_gzip`] handles the buffer management.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::Font;
+use core::fmt;
+
+use crate::ParseError;
+
+const MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// The only compression method defined by RFC 1952
+const DEFLATE: u8 = 8;
+
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+const FRESERVED: u8 = 0xe0;
+
+/// Greatest factor by which deflate can expand its input
+#[cfg(feature = "alloc")]
+const MAX_RATIO: usize = 1032;
+
+/// Whether `data` begins with a gzip header
+#[inline]
+pub fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+/// Size of the data compressed in the gzip stream `data`, modulo 2^32
+///
+/// Use this to size the buffer passed to [`decompress`].
+pub fn decompressed_len(data: &[u8]) -> Result<usize, GzipError> {
+    let trailer = trailer(data)?;
+    Ok(u32::from_le_bytes(trailer[4..8].try_into().unwrap()) as usize)
+}
+
+/// Decompress the gzip stream `data` into `out`, returning the number of bytes written
+///
+/// The integrity of the result is verified against the checksum stored in the stream.
+pub fn decompress(data: &[u8], out: &mut [u8]) -> Result<usize, GzipError> {
+    let body = body(data)?;
+    let len = decompressed_len(data)?;
+    let out = out.get_mut(..len).ok_or(GzipError::BufferTooSmall)?;
+    let written = miniz_oxide::inflate::decompress_slice_iter_to_slice(
+        out,
+        core::iter::once(body),
+        false,
+        true,
+    )
+    .map_err(|_| GzipError::Corrupt)?;
+    let trailer = trailer(data)?;
+    let crc = u32::from_le_bytes(trailer[0..4].try_into().unwrap());
+    if written != len || crc32(&out[..written]) != crc {
+        return Err(GzipError::Corrupt);
+    }
+    Ok(written)
+}
+
+/// The CRC32 and ISIZE fields ending the stream
+fn trailer(data: &[u8]) -> Result<&[u8], GzipError> {
+    let start = data.len().checked_sub(8).ok_or(GzipError::UnexpectedEnd)?;
+    Ok(&data[start..])
+}
+
+/// The deflate stream following the header
+fn body(data: &[u8]) -> Result<&[u8], GzipError> {
+    if !is_gzip(data) {
+        return Err(GzipError::BadMagic);
+    }
+    let header = data.get(0..10).ok_or(GzipError::UnexpectedEnd)?;
+    let flags = header[3];
+    if header[2] != DEFLATE || flags & FRESERVED != 0 {
+        return Err(GzipError::Unsupported);
+    }
+    let mut rest = &data[10..];
+    if flags & FEXTRA != 0 {
+        let len = rest.get(0..2).ok_or(GzipError::UnexpectedEnd)?;
+        let len = usize::from(u16::from_le_bytes([len[0], len[1]]));
+        rest = rest.get(2 + len..).ok_or(GzipError::UnexpectedEnd)?;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = rest
+                .iter()
+                .position(|&x| x == 0)
+                .ok_or(GzipError::UnexpectedEnd)?;
+            rest = &rest[end + 1..];
+        }
+    }
+    if flags & FHCRC != 0 {
+        rest = rest.get(2..).ok_or(GzipError::UnexpectedEnd)?;
+    }
+    let end = rest.len().checked_sub(8).ok_or(GzipError::UnexpectedEnd)?;
+    Ok(&rest[..end])
+}
+
+/// CRC-32 as used by gzip
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(feature = "alloc")]
+impl Font<Vec<u8>> {
+    /// Parse `data` as a PSF1 or PSF2 font, decompressing it first if it is gzipped
+    pub fn from_gzip(data: &[u8]) -> Result<Self, GzipError> {
+        if !is_gzip(data) {
+            return Ok(Font::new(data.to_vec())?);
+        }
+        let len = decompressed_len(data)?;
+        // Don't trust the trailer with an allocation deflate could never fill
+        if len > body(data)?.len().saturating_mul(MAX_RATIO) {
+            return Err(GzipError::Corrupt);
+        }
+        let mut buf = alloc::vec![0; len];
+        let len = decompress(data, &mut buf)?;
+        buf.truncate(len);
+        Ok(Font::new(buf)?)
+    }
+}
+
+/// Why gzipped data might not yield a valid font
+#[derive(Debug, Copy, Clone)]
+pub enum GzipError {
+    /// Input data ended prematurely
+    UnexpectedEnd,
+    /// Missing magic number; probably not gzip data.
+    BadMagic,
+    /// Compression method or flags not defined by RFC 1952
+    Unsupported,
+    /// The compressed data was malformed or failed its checksum
+    Corrupt,
+    /// The output buffer cannot hold the decompressed data
+    BufferTooSmall,
+    /// The decompressed data is not a valid font
+    Font(ParseError),
+}
+
+impl fmt::Display for GzipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            GzipError::UnexpectedEnd => f.write_str("unexpected end of gzip data"),
+            GzipError::BadMagic => f.write_str("missing magic number; not gzip data"),
+            GzipError::Unsupported => f.write_str("unsupported gzip compression method or flags"),
+            GzipError::Corrupt => f.write_str("corrupt gzip data"),
+            GzipError::BufferTooSmall => f.write_str("output buffer too small"),
+            GzipError::Font(_) => f.write_str("invalid font"),
+        }
+    }
+}
+
+impl core::error::Error for GzipError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match *self {
+            GzipError::Font(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for GzipError {
+    fn from(x: ParseError) -> Self {
+        GzipError::Font(x)
+    }
+}
@@ -0,0 +1,170 @@
+//! Constant-time lookup of glyphs by code point
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::fmt;
+
+use crate::{Font, Mapping};
+
+/// Number of code points in the Basic Multilingual Plane
+const BMP_LEN: usize = 0x10000;
+/// Marks a code point with no glyph
+const NONE: u16 = u16::MAX;
+
+/// Precomputed map from code points to glyph indices, avoiding a scan of the Unicode table for
+/// every lookup
+///
+/// Code points in the Basic Multilingual Plane are looked up in a flat table; the rest by binary
+/// search. As with [`Font::get_unicode`], where several glyphs map to one code point the first is
+/// used, and fonts without a Unicode table are assumed to be indexed by code point. Sequences of
+/// code points are not indexed.
+#[derive(Clone)]
+pub struct UnicodeIndex<Bmp, Astral> {
+    bmp: Bmp,
+    astral: Astral,
+}
+
+impl<'a> UnicodeIndex<&'a [u16; BMP_LEN], &'a [(char, u32)]> {
+    /// Number of entries in the table of code points in the Basic Multilingual Plane
+    pub const BMP_LEN: usize = BMP_LEN;
+
+    /// Number of entries needed to index the code points of `font` outside the Basic
+    /// Multilingual Plane
+    pub fn astral_capacity<Data: AsRef<[u8]>>(font: &Font<Data>) -> usize {
+        astral_capacity(font)
+    }
+
+    /// Index `font` using caller-provided storage
+    ///
+    /// `astral` must have room for at least [`astral_capacity`](Self::astral_capacity) entries.
+    pub fn new_in<Data: AsRef<[u8]>>(
+        font: &Font<Data>,
+        bmp: &'a mut [u16; BMP_LEN],
+        astral: &'a mut [(char, u32)],
+    ) -> Result<Self, IndexError> {
+        let len = fill(font, bmp, astral)?;
+        Ok(Self {
+            bmp,
+            astral: &astral[..len],
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl UnicodeIndex<Box<[u16]>, Vec<(char, u32)>> {
+    /// Index `font` in newly allocated storage
+    pub fn new<Data: AsRef<[u8]>>(font: &Font<Data>) -> Result<Self, IndexError> {
+        let mut bmp = vec![NONE; BMP_LEN].into_boxed_slice();
+        let mut astral = vec![('\0', 0); astral_capacity(font)];
+        let len = fill(font, &mut bmp, &mut astral)?;
+        astral.truncate(len);
+        Ok(Self { bmp, astral })
+    }
+}
+
+impl<Bmp, Astral> UnicodeIndex<Bmp, Astral>
+where
+    Bmp: AsRef<[u16]>,
+    Astral: AsRef<[(char, u32)]>,
+{
+    /// Get the index of the glyph for `c`, if present
+    ///
+    /// Pass the result to [`Font::get_index`] to get the glyph itself.
+    #[inline]
+    pub fn get(&self, c: char) -> Option<u32> {
+        match u16::try_from(u32::from(c)) {
+            Ok(x) => {
+                let index = self.bmp.as_ref()[usize::from(x)];
+                (index != NONE).then_some(u32::from(index))
+            }
+            Err(_) => {
+                let astral = self.astral.as_ref();
+                let i = astral.partition_point(|x| x.0 < c);
+                astral.get(i).filter(|x| x.0 == c).map(|x| x.1)
+            }
+        }
+    }
+}
+
+fn astral_capacity<Data: AsRef<[u8]>>(font: &Font<Data>) -> usize {
+    font.glyphs()
+        .flat_map(|(_, _, mappings)| mappings)
+        .filter(|x| matches!(*x, Mapping::Char(c) if u32::from(c) >= BMP_LEN as u32))
+        .count()
+}
+
+/// Populate `bmp` and `astral` from `font`, returning the number of `astral` entries used
+fn fill<Data: AsRef<[u8]>>(
+    font: &Font<Data>,
+    bmp: &mut [u16],
+    astral: &mut [(char, u32)],
+) -> Result<usize, IndexError> {
+    if font.glyph_count() > u32::from(NONE) {
+        return Err(IndexError::TooManyGlyphs {
+            count: font.glyph_count(),
+        });
+    }
+    bmp.fill(NONE);
+    let mut len = 0;
+    for (index, _, mappings) in font.glyphs() {
+        for mapping in mappings {
+            let Mapping::Char(c) = mapping else {
+                continue;
+            };
+            match u16::try_from(u32::from(c)) {
+                Ok(x) => {
+                    let slot = &mut bmp[usize::from(x)];
+                    if *slot == NONE {
+                        *slot = index as u16;
+                    }
+                }
+                Err(_) => {
+                    if len == astral.len() {
+                        return Err(IndexError::AstralCapacity {
+                            needed: astral_capacity(font),
+                            actual: astral.len(),
+                        });
+                    }
+                    astral[len] = (c, index);
+                    len += 1;
+                }
+            }
+        }
+    }
+    // Ties are broken by glyph index, so lookups find the first glyph
+    astral[..len].sort_unstable();
+    Ok(len)
+}
+
+/// Why a font might not be indexable
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IndexError {
+    /// Glyph indices don't fit in the 16-bit entries of the index
+    TooManyGlyphs {
+        /// Number of glyphs in the font
+        count: u32,
+    },
+    /// Storage supplied for code points outside the Basic Multilingual Plane was too small
+    AstralCapacity {
+        /// Number of entries required
+        needed: usize,
+        /// Number of entries supplied
+        actual: usize,
+    },
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            IndexError::TooManyGlyphs { count } => {
+                write!(f, "{count} glyphs is too many to index")
+            }
+            IndexError::AstralCapacity { needed, actual } => write!(
+                f,
+                "astral plane storage has {actual} entries, but {needed} are needed"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for IndexError {}
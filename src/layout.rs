@@ -0,0 +1,469 @@
+//! Arranging text into lines within a box, for drawing by any renderer
+
+use crate::{Font, Glyph, RenderOptions};
+
+/// Horizontal placement of each line within the layout box
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Align {
+    /// Flush with the left edge
+    #[default]
+    Left,
+    /// Centered, rounding towards the left
+    Center,
+    /// Flush with the right edge
+    Right,
+}
+
+/// How to arrange text into lines with [`Font::layout`]
+#[derive(Debug, Copy, Clone)]
+pub struct LayoutOptions {
+    /// Spacing, tab stops and missing-glyph fallback
+    pub render: RenderOptions,
+    /// Width in pixels of the box to fit lines within, or `None` for no limit
+    pub width: Option<u32>,
+    /// Height in pixels of the box to fit lines within, or `None` for no limit
+    pub height: Option<u32>,
+    /// Horizontal placement of each line
+    pub align: Align,
+    /// Whether to break lines that are too wide, rather than truncating them
+    pub wrap: bool,
+    /// Character drawn at the end of truncated lines, or `None` to truncate silently
+    pub ellipsis: Option<char>,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            render: RenderOptions::default(),
+            width: None,
+            height: None,
+            align: Align::Left,
+            wrap: true,
+            ellipsis: Some('…'),
+        }
+    }
+}
+
+impl LayoutOptions {
+    /// Size the box to hold `columns` glyphs by `rows` lines of `font`
+    pub fn with_cells<Data: AsRef<[u8]>>(
+        mut self,
+        font: &Font<Data>,
+        columns: u32,
+        rows: u32,
+    ) -> Self {
+        let spacing = &self.render;
+        self.width = Some(span(columns, font.width(), spacing.letter_spacing));
+        self.height = Some(span(rows, font.height(), spacing.line_spacing));
+        self
+    }
+}
+
+impl<Data: AsRef<[u8]>> Font<Data> {
+    /// Arrange `text` into lines according to `options`
+    ///
+    /// Lines are broken at each `\n`, and, if `options.wrap` is set, wherever needed to fit
+    /// within `options.width`. Breaks follow a simplified subset of the Unicode line breaking
+    /// algorithm: after spaces and hyphens, and between ideographs. Spaces at a break are
+    /// dropped, and a word too long for a line of its own is broken wherever necessary. Lines
+    /// past `options.height` are omitted, and the last line shown is truncated and ended with
+    /// `options.ellipsis` if any text was left out.
+    pub fn layout<'a>(&'a self, text: &'a str, options: &LayoutOptions) -> Layout<'a, Data> {
+        let render = &options.render;
+        let advance = self.width().saturating_add(render.letter_spacing);
+        let line_height = self.height().saturating_add(render.line_spacing);
+        let metrics = Metrics {
+            font: self,
+            advance,
+            letter_spacing: render.letter_spacing,
+            tab_width: render.tab_width.max(1),
+            fallback: render.fallback.and_then(|c| self.get_unicode(c)),
+            ellipsis: options.ellipsis.and_then(|c| self.get_unicode(c)),
+        };
+        let mut result = Layout {
+            metrics,
+            rest: Some(text),
+            y: 0,
+            line_height,
+            max_columns: options
+                .width
+                .map(|x| x.saturating_add(render.letter_spacing) / advance),
+            max_lines: options
+                .height
+                .map(|x| x.saturating_add(render.line_spacing) / line_height),
+            wrap: options.wrap,
+            align: options.align,
+            box_width: options.width.unwrap_or(0),
+        };
+        if options.width.is_none() && options.align != Align::Left {
+            result.box_width = result.clone().map(|line| line.width()).max().unwrap_or(0);
+        }
+        result
+    }
+}
+
+/// Properties shared by every line of a [`Layout`]
+struct Metrics<'a, Data> {
+    font: &'a Font<Data>,
+    advance: u32,
+    letter_spacing: u32,
+    tab_width: u32,
+    fallback: Option<Glyph<'a>>,
+    ellipsis: Option<Glyph<'a>>,
+}
+
+// Manual impl to avoid requiring `Data: Clone`
+impl<Data> Clone for Metrics<'_, Data> {
+    fn clone(&self) -> Self {
+        Self {
+            font: self.font,
+            advance: self.advance,
+            letter_spacing: self.letter_spacing,
+            tab_width: self.tab_width,
+            fallback: self.fallback.clone(),
+            ellipsis: self.ellipsis.clone(),
+        }
+    }
+}
+
+impl<'a, Data: AsRef<[u8]>> Metrics<'a, Data> {
+    /// Column following a glyph unit beginning with `c` placed at `col`
+    #[inline]
+    fn next_column(&self, col: u32, c: char) -> u32 {
+        match c {
+            '\t' => (col / self.tab_width + 1).saturating_mul(self.tab_width),
+            _ => col.saturating_add(1),
+        }
+    }
+
+    /// Iterate over the glyph units of `text` as byte ranges and their first character
+    fn units(&self, text: &'a str) -> impl Iterator<Item = (usize, usize, char)> + 'a {
+        let mut glyphs = self.font.glyphs_for_str(text);
+        core::iter::from_fn(move || {
+            let start = text.len() - glyphs.as_str().len();
+            let c = glyphs.as_str().chars().next()?;
+            glyphs.next();
+            Some((start, text.len() - glyphs.as_str().len(), c))
+        })
+    }
+
+    /// Number of columns occupied by `text`
+    fn columns(&self, text: &'a str) -> u32 {
+        self.units(text)
+            .fold(0, |col, (_, _, c)| self.next_column(col, c))
+    }
+
+    /// Length of the longest prefix of `text` occupying at most `max` columns, excluding
+    /// trailing spaces
+    fn fit(&self, text: &'a str, max: u32) -> usize {
+        let mut col = 0;
+        let mut end = 0;
+        for (_, unit_end, c) in self.units(text) {
+            col = self.next_column(col, c);
+            if col > max {
+                break;
+            }
+            if !is_space(c) {
+                end = unit_end;
+            }
+        }
+        end
+    }
+
+    /// Find where to end the line beginning `para`, which contains no newlines
+    ///
+    /// Returns the end of the line's visible content and the start of the next line.
+    fn wrap(&self, para: &'a str, max_columns: u32) -> (usize, usize) {
+        let mut col = 0;
+        let mut content_end = 0;
+        let mut opportunity = None;
+        let mut prev = None;
+        for (start, end, c) in self.units(para) {
+            if prev.is_some_and(|prev| break_between(prev, c)) {
+                opportunity = Some((content_end, start));
+            }
+            prev = Some(c);
+            col = self.next_column(col, c);
+            if is_space(c) {
+                // Spaces may hang past the edge of the box
+                continue;
+            }
+            if col > max_columns {
+                return match opportunity {
+                    Some(x) => x,
+                    // Break mid-word, but always make progress
+                    None if content_end > 0 => (content_end, start),
+                    None => (end, end),
+                };
+            }
+            content_end = end;
+        }
+        (content_end, para.len())
+    }
+}
+
+/// Iterator over the lines of laid out text
+///
+/// Returned by [`Font::layout`].
+pub struct Layout<'a, Data> {
+    metrics: Metrics<'a, Data>,
+    /// Text not yet laid out, or `None` once finished
+    rest: Option<&'a str>,
+    y: u32,
+    line_height: u32,
+    max_columns: Option<u32>,
+    max_lines: Option<u32>,
+    wrap: bool,
+    align: Align,
+    box_width: u32,
+}
+
+// Manual impl to avoid requiring `Data: Clone`
+impl<Data> Clone for Layout<'_, Data> {
+    fn clone(&self) -> Self {
+        Self {
+            metrics: self.metrics.clone(),
+            rest: self.rest,
+            y: self.y,
+            line_height: self.line_height,
+            max_columns: self.max_columns,
+            max_lines: self.max_lines,
+            wrap: self.wrap,
+            align: self.align,
+            box_width: self.box_width,
+        }
+    }
+}
+
+impl<Data: AsRef<[u8]>> Layout<'_, Data> {
+    /// Width and height in pixels of the remaining lines
+    pub fn size(&self) -> (u32, u32) {
+        let height = self.metrics.font.height();
+        let spacing = self.line_height - height;
+        let (width, lines) = self.clone().fold((0, 0), |(width, lines), line| {
+            (width.max(line.width()), lines + 1)
+        });
+        (width, span(lines, height, spacing))
+    }
+}
+
+impl<'a, Data: AsRef<[u8]>> Iterator for Layout<'a, Data> {
+    type Item = Line<'a, Data>;
+
+    fn next(&mut self) -> Option<Line<'a, Data>> {
+        let rest = self.rest?;
+        let index = self.y / self.line_height;
+        if self.max_lines.is_some_and(|max| index >= max) {
+            self.rest = None;
+            return None;
+        }
+        let metrics = &self.metrics;
+        let para_end = rest.find('\n').unwrap_or(rest.len());
+        let para = &rest[..para_end];
+        let para = para.strip_suffix('\r').unwrap_or(para);
+        let (mut content_end, next) = match self.max_columns {
+            Some(max) if self.wrap => metrics.wrap(para, max),
+            _ => (para.len(), para.len()),
+        };
+        if next < para.len() {
+            self.rest = Some(&rest[next..]);
+        } else if para_end < rest.len() {
+            self.rest = Some(&rest[para_end + 1..]);
+        } else {
+            self.rest = None;
+        }
+
+        let last = self.max_lines.is_some_and(|max| index + 1 == max);
+        let mut truncated = last && self.rest.is_some();
+        let mut columns = metrics.columns(&para[..content_end]);
+        if let Some(max) = self.max_columns {
+            if truncated || columns > max {
+                truncated = true;
+                let room = match metrics.ellipsis {
+                    Some(_) => max.saturating_sub(1),
+                    None => max,
+                };
+                content_end = metrics.fit(&para[..content_end], room);
+                columns = metrics.columns(&para[..content_end]);
+            }
+        }
+        let ellipsis = if truncated {
+            metrics.ellipsis.clone()
+        } else {
+            None
+        };
+
+        let total = columns.saturating_add(u32::from(ellipsis.is_some()));
+        let width = span(total, metrics.font.width(), metrics.letter_spacing);
+        let x = match self.align {
+            Align::Left => 0,
+            Align::Center => self.box_width.saturating_sub(width) / 2,
+            Align::Right => self.box_width.saturating_sub(width),
+        };
+        let y = self.y;
+        self.y = self.y.saturating_add(self.line_height);
+        Some(Line {
+            metrics: metrics.clone(),
+            text: &para[..content_end],
+            x,
+            y,
+            width,
+            truncated,
+            ellipsis,
+        })
+    }
+}
+
+/// A line of laid out text
+pub struct Line<'a, Data> {
+    metrics: Metrics<'a, Data>,
+    text: &'a str,
+    x: u32,
+    y: u32,
+    width: u32,
+    truncated: bool,
+    ellipsis: Option<Glyph<'a>>,
+}
+
+impl<'a, Data: AsRef<[u8]>> Line<'a, Data> {
+    /// The portion of the input text drawn on this line, excluding any ellipsis
+    #[inline]
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Horizontal position in pixels of the left edge of the line
+    #[inline]
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    /// Vertical position in pixels of the top edge of the line
+    #[inline]
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    /// Width in pixels of the line
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Whether the line was cut short, and so ends with an ellipsis if the font has one
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Iterate over the glyphs of the line and their positions
+    pub fn glyphs(&self) -> LineGlyphs<'a, Data> {
+        LineGlyphs {
+            metrics: self.metrics.clone(),
+            glyphs: self.metrics.font.glyphs_for_str(self.text),
+            x: self.x,
+            y: self.y,
+            col: 0,
+            ellipsis: self.ellipsis.clone(),
+        }
+    }
+}
+
+/// A glyph and the position of its top-left corner in pixels
+#[derive(Clone)]
+pub struct PositionedGlyph<'a> {
+    /// Horizontal position of the left edge of the glyph
+    pub x: u32,
+    /// Vertical position of the top edge of the glyph
+    pub y: u32,
+    /// The glyph to draw
+    pub glyph: Glyph<'a>,
+}
+
+/// Iterator over the glyphs of a [`Line`]
+pub struct LineGlyphs<'a, Data> {
+    metrics: Metrics<'a, Data>,
+    glyphs: crate::GlyphsForStr<'a, Data>,
+    x: u32,
+    y: u32,
+    col: u32,
+    ellipsis: Option<Glyph<'a>>,
+}
+
+impl<'a, Data: AsRef<[u8]>> Iterator for LineGlyphs<'a, Data> {
+    type Item = PositionedGlyph<'a>;
+
+    fn next(&mut self) -> Option<PositionedGlyph<'a>> {
+        loop {
+            let x = self
+                .x
+                .saturating_add(self.col.saturating_mul(self.metrics.advance));
+            let Some(c) = self.glyphs.as_str().chars().next() else {
+                let glyph = self.ellipsis.take()?;
+                return Some(PositionedGlyph {
+                    x,
+                    y: self.y,
+                    glyph,
+                });
+            };
+            let glyph = self.glyphs.next()?;
+            self.col = self.metrics.next_column(self.col, c);
+            if c == '\t' {
+                continue;
+            }
+            if let Some(glyph) = glyph.ok().or_else(|| self.metrics.fallback.clone()) {
+                return Some(PositionedGlyph {
+                    x,
+                    y: self.y,
+                    glyph,
+                });
+            }
+        }
+    }
+}
+
+/// Whether `c` is a space, after which lines may break
+fn is_space(c: char) -> bool {
+    // Unicode space separators, except the no-break spaces
+    matches!(
+        c,
+        ' ' | '\t' | '\u{1680}' | '\u{2000}'..='\u{2006}' | '\u{2008}'..='\u{200a}' | '\u{205f}' | '\u{3000}'
+    )
+}
+
+/// Whether `c` is an ideograph or similar, around which lines may break
+fn is_ideographic(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2e80}'..='\u{2fff}'
+            | '\u{3040}'..='\u{30ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{ac00}'..='\u{d7af}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{20000}'..='\u{3ffff}'
+    )
+}
+
+/// Whether a line may break between adjacent glyph units beginning with `before` and `after`
+fn break_between(before: char, after: char) -> bool {
+    if is_space(after) {
+        return false;
+    }
+    match before {
+        _ if is_space(before) => true,
+        '-' => !after.is_ascii_digit(),
+        '\u{2010}' | '\u{2012}' | '\u{2013}' => true,
+        _ => is_ideographic(before) && is_ideographic(after),
+    }
+}
+
+/// Pixels spanned by `count` cells of `size` pixels separated by `spacing`
+fn span(count: u32, size: u32, spacing: u32) -> u32 {
+    match count {
+        0 => 0,
+        _ => (count - 1)
+            .saturating_mul(size.saturating_add(spacing))
+            .saturating_add(size),
+    }
+}
@@ -1,75 +1,147 @@
-//! Parser for v2 [PC Screen Fonts](https://www.win.tue.nl/~aeb/linux/kbd/font-formats-1.html),
+//! Parser for v1 and v2 [PC Screen Fonts](https://www.win.tue.nl/~aeb/linux/kbd/font-formats-1.html),
 //! bitmap fonts which are simple and fast to draw.
 
 #![no_std]
 
-
-
-/// A well-formed PSF2 font
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+use core::fmt;
+
+mod fallback;
+#[cfg(feature = "std")]
+mod fs;
+#[cfg(feature = "embedded-graphics")]
+mod graphics;
+#[cfg(feature = "gzip")]
+pub mod gzip;
+mod index;
+mod layout;
+#[cfg(feature = "alloc")]
+mod merge;
+#[cfg(feature = "alloc")]
+mod owned;
+mod placeholder;
+mod render;
+mod stack;
+#[cfg(feature = "alloc")]
+mod subset;
+mod unicode;
+#[cfg(feature = "alloc")]
+mod write;
+
+pub use fallback::Fallback;
+#[cfg(feature = "std")]
+pub use fs::OpenError;
+#[cfg(feature = "embedded-graphics")]
+pub use graphics::PsfTextStyle;
+pub use index::{IndexError, UnicodeIndex};
+pub use layout::{Align, Layout, LayoutOptions, Line, LineGlyphs, PositionedGlyph};
+#[cfg(feature = "alloc")]
+pub use merge::{Conflict, MergeError, MergeOptions};
+#[cfg(feature = "alloc")]
+pub use owned::{GlyphMut, OwnedFont};
+pub use render::{Canvas, Format, RenderOptions};
+pub use stack::{FontStack, StackError, StackGlyphs};
+#[cfg(feature = "alloc")]
+pub use subset::SubsetOptions;
+pub use unicode::{Mapping, Mappings, Sequence};
+#[cfg(feature = "alloc")]
+pub use write::{FontBuilder, WriteError};
+
+/// A well-formed PSF1 or PSF2 font
 #[derive(Clone)]
 pub struct Font<Data> {
     data: Data,
+    header: Header,
 }
 
 impl<Data: AsRef<[u8]>> Font<Data> {
-    /// Try to parse `data` as a PSF2 font
+    /// Try to parse `data` as a PSF1 or PSF2 font
     pub fn new(data: Data) -> Result<Self, ParseError> {
         let bytes = data.as_ref();
-        let header = bytes.get(0..8 * 4).ok_or(ParseError::UnexpectedEnd)?;
-        if &header[0..4] != &[0x72, 0xb5, 0x4a, 0x86] {
-            return Err(ParseError::BadMagic);
-        }
-
-        let mut result = Self {
-            data,
+        let header = match bytes.get(0..2) {
+            Some(magic) if magic == PSF1_MAGIC => Header::psf1(bytes)?,
+            _ => Header::psf2(bytes)?,
         };
 
-        let glyphs_size = result
-            .charsize()
-            .checked_mul(result.length())
-            .ok_or(ParseError::UnexpectedEnd)?;
-        let glyphs_end = result
-            .headersize()
-            .checked_add(glyphs_size)
-            .ok_or(ParseError::UnexpectedEnd)? as usize;
-
-        if glyphs_end > result.data.as_ref().len() {
-            return Err(ParseError::UnexpectedEnd);
+        let mut result = Self { data, header };
+
+        let glyphs_size = u64::from(result.charsize()) * u64::from(result.glyph_count());
+        let glyphs_end = u64::from(result.headersize()) + glyphs_size;
+        let bytes = result.data.as_ref();
+        if glyphs_end > bytes.len() as u64 {
+            return Err(ParseError::unexpected_end(
+                bytes,
+                result.headersize() as usize,
+                usize::try_from(glyphs_size).unwrap_or(usize::MAX),
+            ));
+        }
+        let glyphs_end = glyphs_end as usize;
+        result.header.glyphs_end = glyphs_end;
+
+        if let Some(encoding) = result.header.unicode {
+            unicode::validate(bytes, glyphs_end, result.glyph_count(), encoding)?;
         }
 
         Ok(result)
     }
 
+    /// The raw data defining the font
     #[inline]
-    fn headersize(&self) -> u32 {
-        u32::from_le_bytes(self.data.as_ref()[8..12].try_into().unwrap())
+    pub fn data(&self) -> &[u8] {
+        self.data.as_ref()
     }
 
+    /// Get back the data the font was parsed from
     #[inline]
-    fn flags(&self) -> u32 {
-        u32::from_le_bytes(self.data.as_ref()[12..16].try_into().unwrap())
+    pub fn into_inner(self) -> Data {
+        self.data
+    }
+
+    /// Copy the font into an owned buffer, without parsing it again
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Font<alloc::vec::Vec<u8>> {
+        Font {
+            data: self.data.as_ref().to_vec(),
+            header: self.header,
+        }
     }
 
     #[inline]
-    fn length(&self) -> u32 {
-        u32::from_le_bytes(self.data.as_ref()[16..20].try_into().unwrap())
+    fn headersize(&self) -> u32 {
+        self.header.headersize
     }
 
     #[inline]
     fn charsize(&self) -> u32 {
-        u32::from_le_bytes(self.data.as_ref()[20..24].try_into().unwrap())
+        self.header.charsize
+    }
+
+    /// Number of glyphs in the font
+    #[inline]
+    pub fn glyph_count(&self) -> u32 {
+        self.header.length
     }
 
     /// Number of rows in a glyph
     #[inline]
     pub fn height(&self) -> u32 {
-        u32::from_le_bytes(self.data.as_ref()[24..28].try_into().unwrap())
+        self.header.height
     }
 
     /// Number of columns in a glyph
     #[inline]
     pub fn width(&self) -> u32 {
-        u32::from_le_bytes(self.data.as_ref()[28..32].try_into().unwrap())
+        self.header.width
+    }
+
+    /// Number of bytes in the bitmap of a glyph
+    #[inline]
+    pub fn glyph_size(&self) -> usize {
+        self.charsize() as usize
     }
 
     /// Get an iterator over the rows of the glyph bitmap for ASCII char `c`, if present
@@ -78,29 +150,378 @@ impl<Data: AsRef<[u8]>> Font<Data> {
         self.get_index(c as u32)
     }
 
+    /// Get an iterator over the rows of the `i`th glyph bitmap, if present
     #[inline]
-    fn get_index(&self, i: u32) -> Option<Glyph<'_>> {
-        let offset = self.headersize() + i * self.charsize();
+    pub fn get_index(&self, i: u32) -> Option<Glyph<'_>> {
+        if i >= self.glyph_count() {
+            return None;
+        }
+        let charsize = self.charsize() as usize;
+        let offset = (i as usize)
+            .checked_mul(charsize)?
+            .checked_add(self.headersize() as usize)?;
         let data = self
             .data
             .as_ref()
-            .get(offset as usize..(offset + self.charsize()) as usize)?;
+            .get(offset..offset.checked_add(charsize)?)?;
         Some(Glyph {
             data,
             width: self.width() as usize,
         })
     }
+
+    /// Get an iterator over the rows of the glyph bitmap for `c`, if present
+    ///
+    /// Fonts without a Unicode table are assumed to be indexed by code point.
+    pub fn get_unicode(&self, c: char) -> Option<Glyph<'_>> {
+        let index = match self.unicode_entries() {
+            Some(mut entries) => entries.position(|entry| entry.chars().any(|x| x == c))? as u32,
+            None => c as u32,
+        };
+        self.get_index(index)
+    }
+
+    /// Iterate over every glyph in the font, along with the code points that map to it
+    ///
+    /// Fonts without a Unicode table are assumed to be indexed by code point.
+    pub fn glyphs(&self) -> Glyphs<'_, Data> {
+        Glyphs {
+            font: self,
+            index: 0,
+            entries: self.unicode_entries(),
+        }
+    }
+
+    /// Get the glyph for a sequence of code points, e.g. a letter followed by combining accents
+    ///
+    /// A single code point is looked up as if by [`get_unicode`](Self::get_unicode).
+    pub fn get_sequence(&self, seq: &[char]) -> Option<Glyph<'_>> {
+        match *seq {
+            [] => return None,
+            [c] => return self.get_unicode(c),
+            _ => {}
+        }
+        let index = self
+            .unicode_entries()?
+            .position(|entry| entry.sequences().any(|x| x.eq(seq.iter().copied())))?;
+        self.get_index(index as u32)
+    }
+
+    /// Iterate over the glyphs needed to draw `text`
+    ///
+    /// Where the Unicode table maps a sequence of code points to one glyph, the longest matching
+    /// sequence is preferred, so precomposed glyphs are used for decomposed input. Characters with
+    /// no glyph are yielded as errors.
+    pub fn glyphs_for_str<'a>(&'a self, text: &'a str) -> GlyphsForStr<'a, Data> {
+        GlyphsForStr { font: self, text }
+    }
+
+    /// Find the glyph matching the longest prefix of `text`, and the length of that prefix
+    fn longest_match(&self, text: &str) -> Option<(usize, u32)> {
+        let c = text.chars().next()?;
+        let Some(entries) = self.unicode_entries() else {
+            return ((c as u32) < self.glyph_count()).then_some((c.len_utf8(), c as u32));
+        };
+        let mut best = None;
+        for (index, entry) in entries.enumerate() {
+            let index = index as u32;
+            if best.is_none() && entry.chars().any(|x| x == c) {
+                best = Some((c.len_utf8(), index));
+            }
+            for seq in entry.sequences() {
+                let Some(len) = unicode::match_prefix(seq, text) else {
+                    continue;
+                };
+                if len > best.map_or(0, |(best_len, _)| best_len) {
+                    best = Some((len, index));
+                }
+            }
+        }
+        best
+    }
+
+    fn unicode_entries(&self) -> Option<unicode::Entries<'_>> {
+        let encoding = self.header.unicode?;
+        Some(unicode::Entries::new(
+            &self.data.as_ref()[self.header.glyphs_end..],
+            self.glyph_count(),
+            encoding,
+        ))
+    }
+}
+
+/// Iterator over every glyph in a font
+///
+/// Returned by [`Font::glyphs`]. Yields each glyph's index, bitmap, and Unicode mappings.
+pub struct Glyphs<'a, Data> {
+    font: &'a Font<Data>,
+    index: u32,
+    entries: Option<unicode::Entries<'a>>,
+}
+
+// Manual impl to avoid requiring `Data: Clone`
+impl<Data> Clone for Glyphs<'_, Data> {
+    fn clone(&self) -> Self {
+        Self {
+            font: self.font,
+            index: self.index,
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<'a, Data: AsRef<[u8]>> Iterator for Glyphs<'a, Data> {
+    type Item = (u32, Glyph<'a>, Mappings<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<(u32, Glyph<'a>, Mappings<'a>)> {
+        let index = self.index;
+        let glyph = self.font.get_index(index)?;
+        let mappings = match self.entries {
+            Some(ref mut entries) => entries.next()?.mappings(),
+            None => Mappings::implicit(index),
+        };
+        self.index += 1;
+        Some((index, glyph, mappings))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<Data: AsRef<[u8]>> ExactSizeIterator for Glyphs<'_, Data> {
+    #[inline]
+    fn len(&self) -> usize {
+        (self.font.glyph_count() - self.index) as usize
+    }
+}
+
+/// Iterator over the glyphs needed to draw a string
+///
+/// Returned by [`Font::glyphs_for_str`].
+pub struct GlyphsForStr<'a, Data> {
+    font: &'a Font<Data>,
+    text: &'a str,
+}
+
+// Manual impl to avoid requiring `Data: Clone`
+impl<Data> Clone for GlyphsForStr<'_, Data> {
+    fn clone(&self) -> Self {
+        Self {
+            font: self.font,
+            text: self.text,
+        }
+    }
+}
+
+impl<'a, Data> GlyphsForStr<'a, Data> {
+    /// The text not yet iterated through
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+}
+
+impl<'a, Data: AsRef<[u8]>> Iterator for GlyphsForStr<'a, Data> {
+    type Item = Result<Glyph<'a>, char>;
+
+    fn next(&mut self) -> Option<Result<Glyph<'a>, char>> {
+        let c = self.text.chars().next()?;
+        let (len, glyph) = match self.font.longest_match(self.text) {
+            Some((len, index)) => (len, self.font.get_index(index)),
+            None => (c.len_utf8(), None),
+        };
+        self.text = &self.text[len..];
+        Some(glyph.ok_or(c))
+    }
+}
+
+/// Layout of a font, common to both versions of the format
+#[derive(Copy, Clone)]
+struct Header {
+    headersize: u32,
+    length: u32,
+    charsize: u32,
+    height: u32,
+    width: u32,
+    unicode: Option<unicode::Encoding>,
+    /// Offset of the first byte after the glyphs, known to be within the data once parsed
+    glyphs_end: usize,
+}
+
+impl Header {
+    fn psf1(bytes: &[u8]) -> Result<Self, ParseError> {
+        let header = bytes
+            .get(0..4)
+            .ok_or_else(|| ParseError::unexpected_end(bytes, 0, 4))?;
+        let mode = header[2];
+        let charsize = u32::from(header[3]);
+        let result = Self {
+            headersize: 4,
+            length: if mode & PSF1_MODE512 != 0 { 512 } else { 256 },
+            charsize,
+            height: charsize,
+            width: 8,
+            unicode: (mode & (PSF1_MODEHASTAB | PSF1_MODEHASSEQ) != 0)
+                .then_some(unicode::Encoding::Ucs2),
+            glyphs_end: 0,
+        };
+        result.validate()?;
+        Ok(result)
+    }
+
+    fn psf2(bytes: &[u8]) -> Result<Self, ParseError> {
+        let header = bytes
+            .get(0..8 * 4)
+            .ok_or_else(|| ParseError::unexpected_end(bytes, 0, 8 * 4))?;
+        if header[0..4] != PSF2_MAGIC {
+            return Err(ParseError::BadMagic);
+        }
+        let field = |i: usize| u32::from_le_bytes(header[i * 4..(i + 1) * 4].try_into().unwrap());
+        let version = field(1);
+        if version != 0 {
+            return Err(ParseError::UnsupportedVersion { version });
+        }
+        let result = Self {
+            headersize: field(2),
+            length: field(4),
+            charsize: field(5),
+            height: field(6),
+            width: field(7),
+            unicode: (field(3) & PSF2_HAS_UNICODE_TABLE != 0).then_some(unicode::Encoding::Utf8),
+            glyphs_end: 0,
+        };
+        if result.headersize < 8 * 4 {
+            return Err(ParseError::BadHeaderSize {
+                headersize: result.headersize,
+            });
+        }
+        result.validate()?;
+        Ok(result)
+    }
+
+    /// Check invariants common to both versions
+    fn validate(&self) -> Result<(), ParseError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(ParseError::ZeroDimension {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        let expected = self.height.saturating_mul(self.width.div_ceil(8));
+        if self.charsize != expected {
+            return Err(ParseError::CharsizeMismatch {
+                charsize: self.charsize,
+                expected,
+            });
+        }
+        Ok(())
+    }
 }
 
-/// Why data might not be a valid PSF2 font
-#[derive(Debug, Copy, Clone)]
+/// Why data might not be a valid PSF font
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ParseError {
     /// Input data ended prematurely
-    UnexpectedEnd,
+    UnexpectedEnd {
+        /// Byte offset of the structure that was cut off
+        offset: usize,
+        /// Minimum number of bytes needed from `offset`
+        expected: usize,
+        /// Number of bytes actually present from `offset`
+        actual: usize,
+    },
     /// Missing magic number; probably not PSF data.
     BadMagic,
+    /// PSF2 version other than 0
+    UnsupportedVersion {
+        /// Version stored in the header
+        version: u32,
+    },
+    /// PSF2 header claims to be smaller than the fixed header fields
+    BadHeaderSize {
+        /// Header size stored in the header
+        headersize: u32,
+    },
+    /// Glyph size inconsistent with the glyph dimensions
+    CharsizeMismatch {
+        /// Bytes per glyph stored in the header
+        charsize: u32,
+        /// Bytes per glyph implied by the width and height
+        expected: u32,
+    },
+    /// Glyphs have no rows or no columns
+    ZeroDimension {
+        /// Number of columns stored in the header
+        width: u32,
+        /// Number of rows stored in the header
+        height: u32,
+    },
+    /// The Unicode table contained malformed UTF-8 or UCS-2
+    BadUnicodeTable {
+        /// Byte offset of the malformed code point from the start of the font
+        offset: usize,
+    },
+}
+
+impl ParseError {
+    fn unexpected_end(data: &[u8], offset: usize, expected: usize) -> Self {
+        ParseError::UnexpectedEnd {
+            offset,
+            expected,
+            actual: data.len().saturating_sub(offset),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ParseError::UnexpectedEnd {
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "unexpected end of data at byte {offset}: expected at least {expected} bytes, found {actual}"
+            ),
+            ParseError::BadMagic => f.write_str("missing magic number; not a PSF font"),
+            ParseError::UnsupportedVersion { version } => {
+                write!(f, "unsupported PSF2 version {version}")
+            }
+            ParseError::BadHeaderSize { headersize } => {
+                write!(f, "header size {headersize} is smaller than the PSF2 header")
+            }
+            ParseError::CharsizeMismatch { charsize, expected } => write!(
+                f,
+                "glyph size {charsize} does not match dimensions, which imply {expected}"
+            ),
+            ParseError::ZeroDimension { width, height } => {
+                write!(f, "glyph dimensions {width}x{height} are empty")
+            }
+            ParseError::BadUnicodeTable { offset } => {
+                write!(f, "malformed code point in Unicode table at byte {offset}")
+            }
+        }
+    }
 }
 
+impl core::error::Error for ParseError {}
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+/// Mode flag indicating that the font has 512 glyphs rather than 256
+const PSF1_MODE512: u8 = 0x01;
+/// Mode flag indicating that the glyphs are followed by a Unicode table
+const PSF1_MODEHASTAB: u8 = 0x02;
+/// Mode flag indicating that the Unicode table contains sequences
+const PSF1_MODEHASSEQ: u8 = 0x04;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+/// Flag indicating that the glyphs are followed by a Unicode table
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+
 /// Iterator over each row of a glyph
 #[derive(Clone)]
 pub struct Glyph<'a> {
@@ -116,13 +537,153 @@ impl<'a> Glyph<'a> {
     pub fn data(&self) -> &'a [u8] {
         self.data
     }
+
+    /// Number of columns in the glyph
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width as u32
+    }
+
+    /// Number of rows in the glyph, minus any already iterated through
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.len() as u32
+    }
+
+    /// Get an iterator over row `y`, counting from the first row not yet iterated through
+    #[inline]
+    pub fn row(&self, y: u32) -> Option<GlyphRow<'a>> {
+        let stride = self.width.div_ceil(8);
+        let start = (y as usize).checked_mul(stride)?;
+        let data = self.data.get(start..start.checked_add(stride)?)?;
+        Some(GlyphRow {
+            data,
+            bit: 0,
+            width: self.width,
+        })
+    }
+
+    /// Whether the pixel in column `x` of row `y` is filled, if it exists
+    #[inline]
+    pub fn pixel(&self, x: u32, y: u32) -> Option<bool> {
+        let x = x as usize;
+        if x >= self.width {
+            return None;
+        }
+        let byte = self.row(y)?.data[x >> 3];
+        Some(byte & BITS[x & 7] != 0)
+    }
+
+    /// Draw the remaining rows into `target`, with the top-left pixel at `target[0]`
+    ///
+    /// Rows begin `stride` elements apart. Filled pixels are set to `fg`, and the rest to `bg`, or
+    /// left untouched if `bg` is `None`. Pixels which would fall past the end of `target` or of a
+    /// row of `stride` pixels are skipped.
+    pub fn blit<P: Copy>(&self, target: &mut [P], stride: usize, fg: P, bg: Option<P>) {
+        let cols = self.width.min(stride);
+        let rows = self.data.chunks_exact(self.width.div_ceil(8));
+        for (row, out) in rows.zip(target.chunks_mut(stride.max(1))) {
+            let len = cols.min(out.len());
+            blit_row(row, &mut out[..len], fg, bg);
+        }
+    }
+
+    /// Draw the remaining rows into a framebuffer of `N`-byte pixels, e.g. 8, 16, 24 or 32-bit
+    ///
+    /// Rows begin `pitch` bytes apart. Otherwise equivalent to [`blit`](Self::blit).
+    pub fn blit_bytes<const N: usize>(
+        &self,
+        target: &mut [u8],
+        pitch: usize,
+        fg: [u8; N],
+        bg: Option<[u8; N]>,
+    ) {
+        let cols = self.width.min(pitch / N);
+        let rows = self.data.chunks_exact(self.width.div_ceil(8));
+        for (row, out) in rows.zip(target.chunks_mut(pitch.max(1))) {
+            let (out, _) = out.as_chunks_mut::<N>();
+            let len = cols.min(out.len());
+            blit_row(row, &mut out[..len], fg, bg);
+        }
+    }
+
+    /// Get an iterator over the remaining rows as bitmasks, if the glyph is at most 32 pixels wide
+    ///
+    /// See [`GlyphRow::as_u32`].
+    #[inline]
+    pub fn rows_u32(&self) -> Option<RowsU32<'a>> {
+        (self.width <= 32).then(|| RowsU32(self.clone()))
+    }
+}
+
+/// Draw the row of pixels defined by the bitfield `row` into `out`, which must be no longer than
+/// the row
+#[inline(always)]
+fn blit_row<P: Copy>(row: &[u8], out: &mut [P], fg: P, bg: Option<P>) {
+    match bg {
+        Some(bg) => {
+            for (chunk, &byte) in out.chunks_mut(8).zip(row) {
+                for (px, &bit) in chunk.iter_mut().zip(&BITS) {
+                    *px = if byte & bit != 0 { fg } else { bg };
+                }
+            }
+        }
+        None => {
+            // Visit only the filled pixels
+            for (chunk, &byte) in out.chunks_mut(8).zip(row) {
+                let mut byte = byte;
+                while byte != 0 {
+                    let x = byte.leading_zeros() as usize;
+                    let Some(px) = chunk.get_mut(x) else {
+                        break;
+                    };
+                    *px = fg;
+                    byte &= !BITS[x];
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over each row of a glyph as a bitmask
+///
+/// Returned by [`Glyph::rows_u32`].
+#[derive(Clone)]
+pub struct RowsU32<'a>(Glyph<'a>);
+
+impl Iterator for RowsU32<'_> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<u32> {
+        self.0.next()?.as_u32()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for RowsU32<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl DoubleEndedIterator for RowsU32<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<u32> {
+        self.0.next_back()?.as_u32()
+    }
 }
 
 impl<'a> Iterator for Glyph<'a> {
     type Item = GlyphRow<'a>;
     #[inline]
     fn next(&mut self) -> Option<GlyphRow<'a>> {
-        let advance = (self.width + 7) / 8;
+        let advance = self.width.div_ceil(8);
         if self.data.len() < advance {
             return None;
         }
@@ -144,14 +705,17 @@ impl<'a> Iterator for Glyph<'a> {
 impl ExactSizeIterator for Glyph<'_> {
     #[inline]
     fn len(&self) -> usize {
-        self.data.len() / self.width
+        self.data
+            .len()
+            .checked_div(self.width.div_ceil(8))
+            .unwrap_or(0)
     }
 }
 
 impl<'a> DoubleEndedIterator for Glyph<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<GlyphRow<'a>> {
-        let advance = (self.width + 7) / 8;
+        let advance = self.width.div_ceil(8);
         if self.data.len() < advance {
             return None;
         }
@@ -175,6 +739,29 @@ pub struct GlyphRow<'a> {
     width: usize,
 }
 
+/// Generate a `GlyphRow` method returning the row as an integer bitmask
+macro_rules! as_uint {
+    ($(#[$doc:meta])* $name:ident, $ty:ty) => {
+        $(#[$doc])*
+        ///
+        /// The most significant bit corresponds to the leftmost pixel. Bits past the end of the row,
+        /// or for pixels already iterated through, are zero.
+        #[inline]
+        pub fn $name(&self) -> Option<$ty> {
+            const BYTES: usize = <$ty>::BITS as usize / 8;
+            if self.data.len() > BYTES {
+                return None;
+            }
+            let mut buf = [0; BYTES];
+            buf[..self.data.len()].copy_from_slice(self.data);
+            let raw = <$ty>::from_be_bytes(buf);
+            let mask = <$ty>::MAX.checked_shr(self.bit as u32).unwrap_or(0)
+                & !<$ty>::MAX.checked_shr(self.width as u32).unwrap_or(0);
+            Some(raw & mask)
+        }
+    };
+}
+
 impl<'a> GlyphRow<'a> {
     /// A bitfield defining the filled pixels in this row of the glyph
     ///
@@ -183,6 +770,32 @@ impl<'a> GlyphRow<'a> {
     pub fn data(&self) -> &'a [u8] {
         self.data
     }
+
+    as_uint!(
+        /// The row as a bitmask, if it is at most 8 pixels wide
+        as_u8,
+        u8
+    );
+    as_uint!(
+        /// The row as a bitmask, if it is at most 16 pixels wide
+        as_u16,
+        u16
+    );
+    as_uint!(
+        /// The row as a bitmask, if it is at most 32 pixels wide
+        as_u32,
+        u32
+    );
+    as_uint!(
+        /// The row as a bitmask, if it is at most 64 pixels wide
+        as_u64,
+        u64
+    );
+    as_uint!(
+        /// The row as a bitmask, if it is at most 128 pixels wide
+        as_u128,
+        u128
+    );
 }
 
 impl<'a> Iterator for GlyphRow<'a> {
@@ -244,7 +857,7 @@ const BITS: [u8; 8] = [
     1 << 0,
 ];
 
-#[cfg(all(test, feature = "std"))]
+#[cfg(test)]
 mod tests {
     use std::vec::Vec;
 
@@ -283,7 +896,59 @@ mod tests {
             width: 1,
         };
         assert_eq!(it.len(), 2);
-        assert_eq!(it.flat_map(|x| x).collect::<Vec<_>>(), &[true, false]);
+        assert_eq!(it.flatten().collect::<Vec<_>>(), &[true, false]);
+    }
+
+    #[test]
+    fn random_access() {
+        let mut it = Glyph {
+            data: &[0b1000_0000, 0b0100_0000, 0, 0b0000_0001],
+            width: 10,
+        };
+        assert_eq!((it.width(), it.height()), (10, 2));
+        assert_eq!(it.pixel(0, 0), Some(true));
+        assert_eq!(it.pixel(9, 0), Some(true));
+        assert_eq!(it.pixel(1, 0), Some(false));
+        assert_eq!(it.pixel(10, 0), None);
+        assert_eq!(it.pixel(0, 2), None);
+        assert_eq!(it.row(1).unwrap().data(), &[0, 1]);
+        assert!(it.row(2).is_none());
+
+        it.next();
+        assert_eq!(it.height(), 1);
+        assert_eq!(it.pixel(0, 0), Some(false));
+        assert_eq!(it.pixel(15, 0), None);
+        assert_eq!(it.pixel(0, u32::MAX), None);
+    }
+
+    #[test]
+    fn bitmasks() {
+        let mut it = GlyphRow {
+            data: &[0b1010_0000, 0b1111_1111],
+            bit: 0,
+            width: 10,
+        };
+        assert_eq!(it.as_u8(), None);
+        assert_eq!(it.as_u16(), Some(0b1010_0000_1100_0000));
+        assert_eq!(it.as_u32(), Some(0b1010_0000_1100_0000 << 16));
+        assert_eq!(it.as_u128(), Some(0b1010_0000_1100_0000 << 112));
+        it.next();
+        it.next_back();
+        assert_eq!(it.as_u16(), Some(0b0010_0000_1000_0000));
+
+        let glyph = Glyph {
+            data: &[0xff, 0x80],
+            width: 1,
+        };
+        assert_eq!(
+            glyph.rows_u32().unwrap().rev().collect::<Vec<_>>(),
+            &[1 << 31, 1 << 31]
+        );
+        let glyph = Glyph {
+            data: &[0; 10],
+            width: 33,
+        };
+        assert!(glyph.rows_u32().is_none());
     }
 
     #[test]
@@ -292,8 +957,8 @@ mod tests {
             data: &[128, 0],
             width: 1,
         };
-        let mut naive = it.clone().flat_map(|x| x).collect::<Vec<_>>();
+        let mut naive = it.clone().flatten().collect::<Vec<_>>();
         naive.reverse();
-        assert_eq!(naive, it.rev().flat_map(|x| x).collect::<Vec<_>>());
+        assert_eq!(naive, it.rev().flatten().collect::<Vec<_>>());
     }
 }
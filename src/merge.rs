@@ -0,0 +1,194 @@
+//! Combining several fonts into one
+
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use core::fmt;
+
+use crate::{owned::Entry, Font, OwnedFont};
+
+/// How [`OwnedFont::merge`] combines fonts
+#[derive(Debug, Copy, Clone)]
+pub struct MergeOptions {
+    /// What to do when several fonts map the same character or sequence
+    pub conflict: Conflict,
+    /// Whether to store glyphs with identical bitmaps only once
+    pub dedupe: bool,
+    /// Most glyphs the result may have, e.g. `Some(512)` for the Linux console
+    pub max_glyphs: Option<u32>,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            conflict: Conflict::FirstWins,
+            dedupe: true,
+            max_glyphs: None,
+        }
+    }
+}
+
+/// Which glyph a character or sequence mapped by several fonts resolves to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// The glyph from the earliest font that maps it
+    FirstWins,
+    /// The glyph from the latest font that maps it
+    LastWins,
+    /// Fail with [`MergeError::Conflict`]
+    Error,
+}
+
+impl OwnedFont {
+    /// Combine the glyphs and mappings of `fonts`, which must have glyphs of equal size
+    ///
+    /// Fonts without a Unicode table contribute their glyphs as mapped by code point. Glyphs
+    /// appear in the order of their fonts, except that those left without any mapping are
+    /// dropped, and with [`MergeOptions::dedupe`] each glyph is stored where its bitmap first
+    /// occurs.
+    pub fn merge<Data: AsRef<[u8]>>(
+        fonts: &[Font<Data>],
+        options: &MergeOptions,
+    ) -> Result<Self, MergeError> {
+        let first = fonts.first().ok_or(MergeError::Empty)?;
+        let (width, height) = (first.width(), first.height());
+        let mut bitmaps = Vec::new();
+        let mut entries = Vec::<Entry>::new();
+        // Font mapping each character or sequence, and the glyphs it maps to
+        let mut owners = BTreeMap::<Vec<char>, (usize, Vec<usize>)>::new();
+        for (font_index, font) in fonts.iter().enumerate() {
+            if (font.width(), font.height()) != (width, height) {
+                return Err(MergeError::SizeMismatch {
+                    index: font_index,
+                    width: font.width(),
+                    height: font.height(),
+                });
+            }
+            let source = OwnedFont::with_table(font);
+            bitmaps.extend_from_slice(&source.bitmaps);
+            for (index, entry) in source.entries.into_iter().enumerate() {
+                let glyph = entries.len();
+                let mut claim = |seq: &[char]| -> Result<bool, MergeError> {
+                    match owners.get_mut(seq) {
+                        None => {
+                            owners.insert(seq.to_vec(), (font_index, vec![glyph]));
+                        }
+                        // Duplicates within a font are left as they are
+                        Some((owner, glyphs)) if *owner == font_index => glyphs.push(glyph),
+                        Some((owner, glyphs)) => match options.conflict {
+                            Conflict::FirstWins => return Ok(false),
+                            Conflict::LastWins => {
+                                for &i in &*glyphs {
+                                    let entry = &mut entries[i];
+                                    entry.chars.retain(|&c| [c] != seq);
+                                    entry.sequences.retain(|x| x != seq);
+                                }
+                                *owner = font_index;
+                                *glyphs = vec![glyph];
+                            }
+                            Conflict::Error => {
+                                return Err(MergeError::Conflict {
+                                    index: font_index,
+                                    glyph: index as u32,
+                                })
+                            }
+                        },
+                    }
+                    Ok(true)
+                };
+                let mut kept = Entry::default();
+                for c in entry.chars {
+                    if claim(&[c])? {
+                        kept.chars.push(c);
+                    }
+                }
+                for seq in entry.sequences {
+                    if claim(&seq)? {
+                        kept.sequences.push(seq);
+                    }
+                }
+                entries.push(kept);
+            }
+        }
+
+        let mut result = OwnedFont::new(width, height);
+        result.has_table = true;
+        let size = result.glyph_size();
+        // Index in `result` of each distinct bitmap
+        let mut seen = BTreeMap::<&[u8], usize>::new();
+        for (bitmap, entry) in bitmaps.chunks_exact(size).zip(entries) {
+            if entry.chars.is_empty() && entry.sequences.is_empty() {
+                continue;
+            }
+            if options.dedupe {
+                if let Some(&i) = seen.get(bitmap) {
+                    let existing = &mut result.entries[i];
+                    existing.chars.extend(entry.chars);
+                    existing.sequences.extend(entry.sequences);
+                    continue;
+                }
+                seen.insert(bitmap, result.entries.len());
+            }
+            result.bitmaps.extend_from_slice(bitmap);
+            result.entries.push(entry);
+        }
+
+        let count = result.glyph_count();
+        if options.max_glyphs.is_some_and(|max| count > max) {
+            return Err(MergeError::TooManyGlyphs { count });
+        }
+        Ok(result)
+    }
+}
+
+/// Why fonts might not be mergeable
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    /// No fonts were supplied
+    Empty,
+    /// A font's glyphs differ in size from those of the first font
+    SizeMismatch {
+        /// Position of the offending font in the list
+        index: usize,
+        /// Number of columns in the font's glyphs
+        width: u32,
+        /// Number of rows in the font's glyphs
+        height: u32,
+    },
+    /// A glyph maps a character or sequence already mapped by an earlier font, under
+    /// [`Conflict::Error`]
+    Conflict {
+        /// Position of the offending font in the list
+        index: usize,
+        /// Index of the glyph within that font
+        glyph: u32,
+    },
+    /// The merged font has more glyphs than [`MergeOptions::max_glyphs`]
+    TooManyGlyphs {
+        /// Number of glyphs in the merged font
+        count: u32,
+    },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            MergeError::Empty => f.write_str("no fonts to merge"),
+            MergeError::SizeMismatch {
+                index,
+                width,
+                height,
+            } => write!(
+                f,
+                "font {index} has {width}x{height} glyphs, unlike the first font"
+            ),
+            MergeError::Conflict { index, glyph } => write!(
+                f,
+                "glyph {glyph} of font {index} maps a character already mapped by an earlier font"
+            ),
+            MergeError::TooManyGlyphs { count } => {
+                write!(f, "merged font has {count} glyphs, more than allowed")
+            }
+        }
+    }
+}
+
+impl core::error::Error for MergeError {}
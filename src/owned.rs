@@ -0,0 +1,408 @@
+//! A font held in editable form
+
+use alloc::vec::Vec;
+
+use crate::{Font, Glyph, Mapping, WriteError};
+
+/// A font whose glyphs and Unicode mappings can be modified
+///
+/// Created empty, from a [`FontBuilder`](crate::FontBuilder), or from any [`Font`], and written
+/// back out with [`to_psf2`](Self::to_psf2) or [`to_psf1`](Self::to_psf1). A font has a Unicode
+/// table if it was read with one or once any glyph is mapped, and is otherwise taken to be indexed
+/// by code point. Mapping a glyph in such a font first maps every existing glyph to its code
+/// point, so that lookups keep working, and removing mappings never takes the table away.
+#[derive(Debug, Clone)]
+pub struct OwnedFont {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// Concatenated glyph bitmaps
+    pub(crate) bitmaps: Vec<u8>,
+    pub(crate) entries: Vec<Entry>,
+    /// Whether glyphs are looked up through `entries` rather than by code point
+    pub(crate) has_table: bool,
+    /// Whether unmapped glyphs are awaiting mappings, as in a [`FontBuilder`](crate::FontBuilder),
+    /// rather than indexed by code point
+    pub(crate) building: bool,
+}
+
+/// The code points mapping to a glyph
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Entry {
+    pub(crate) chars: Vec<char>,
+    pub(crate) sequences: Vec<Vec<char>>,
+}
+
+impl OwnedFont {
+    /// Create a font with no glyphs, each of which will be `width` by `height` pixels
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            bitmaps: Vec::new(),
+            entries: Vec::new(),
+            has_table: false,
+            building: false,
+        }
+    }
+
+    /// Number of columns in a glyph
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Number of rows in a glyph
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Number of bytes in the bitmap of a glyph
+    #[inline]
+    pub fn glyph_size(&self) -> usize {
+        self.height as usize * self.width.div_ceil(8) as usize
+    }
+
+    /// Number of glyphs in the font
+    #[inline]
+    pub fn glyph_count(&self) -> u32 {
+        self.entries.len() as u32
+    }
+
+    /// Get an iterator over the rows of the `i`th glyph bitmap, if present
+    pub fn get_index(&self, i: u32) -> Option<Glyph<'_>> {
+        let size = self.glyph_size();
+        let start = (i as usize).checked_mul(size)?;
+        Some(Glyph {
+            data: self.bitmaps.get(start..start.checked_add(size)?)?,
+            width: self.width as usize,
+        })
+    }
+
+    /// Get an iterator over the rows of the glyph bitmap for `c`, if present
+    pub fn get_unicode(&self, c: char) -> Option<Glyph<'_>> {
+        self.get_index(self.find(c)?)
+    }
+
+    /// Index of the glyph for `c`, if present
+    ///
+    /// Where several glyphs map to `c`, the first is used, as by [`Font::get_unicode`].
+    pub fn find(&self, c: char) -> Option<u32> {
+        if !self.has_unicode_table() {
+            return ((c as u32) < self.glyph_count()).then_some(c as u32);
+        }
+        self.entries
+            .iter()
+            .position(|x| x.chars.contains(&c))
+            .map(|x| x as u32)
+    }
+
+    /// Get a mutable view of the `i`th glyph bitmap, if present
+    pub fn glyph_mut(&mut self, i: u32) -> Option<GlyphMut<'_>> {
+        let size = self.glyph_size();
+        let start = (i as usize).checked_mul(size)?;
+        Some(GlyphMut {
+            data: self.bitmaps.get_mut(start..start.checked_add(size)?)?,
+            width: self.width,
+            height: self.height,
+        })
+    }
+
+    /// Add a glyph drawn by `bitmap` to the end of the font, returning its index
+    ///
+    /// Each of `chars` is mapped to the new glyph.
+    pub fn push_glyph(&mut self, bitmap: &[u8], chars: &[char]) -> Result<u32, WriteError> {
+        let index = self.glyph_count();
+        self.insert_glyph(index, bitmap, chars)?;
+        Ok(index)
+    }
+
+    /// Add a glyph drawn by `bitmap` at `index`, moving later glyphs up by one
+    ///
+    /// Each of `chars` is mapped to the new glyph.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is greater than [`glyph_count`](Self::glyph_count).
+    pub fn insert_glyph(
+        &mut self,
+        index: u32,
+        bitmap: &[u8],
+        chars: &[char],
+    ) -> Result<(), WriteError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(WriteError::ZeroDimension {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        if bitmap.len() != self.glyph_size() {
+            return Err(WriteError::BitmapSize {
+                expected: self.glyph_size(),
+                actual: bitmap.len(),
+            });
+        }
+        if !chars.is_empty() {
+            self.start_table();
+        }
+        let index = index as usize;
+        self.entries.insert(
+            index,
+            Entry {
+                chars: chars.to_vec(),
+                sequences: Vec::new(),
+            },
+        );
+        let start = index * bitmap.len();
+        self.bitmaps.splice(start..start, bitmap.iter().copied());
+        Ok(())
+    }
+
+    /// Remove the glyph at `index` and its mappings, moving later glyphs down by one
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`glyph_count`](Self::glyph_count).
+    pub fn remove_glyph(&mut self, index: u32) {
+        let index = index as usize;
+        self.entries.remove(index);
+        let size = self.glyph_size();
+        self.bitmaps.drain(index * size..(index + 1) * size);
+    }
+
+    /// Code points which individually map to the `index`th glyph
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`glyph_count`](Self::glyph_count).
+    pub fn chars(&self, index: u32) -> &[char] {
+        &self.entries[index as usize].chars
+    }
+
+    /// Sequences of code points which together map to the `index`th glyph
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`glyph_count`](Self::glyph_count).
+    pub fn sequences(&self, index: u32) -> &[Vec<char>] {
+        &self.entries[index as usize].sequences
+    }
+
+    /// Map `c` to the `index`th glyph
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`glyph_count`](Self::glyph_count).
+    pub fn add_char(&mut self, index: u32, c: char) {
+        assert!(index < self.glyph_count(), "glyph index out of range");
+        self.start_table();
+        self.entries[index as usize].chars.push(c);
+    }
+
+    /// Map a sequence of code points, e.g. a letter followed by combining accents, to the
+    /// `index`th glyph
+    ///
+    /// A sequence of one code point is added as if by [`add_char`](Self::add_char), and an
+    /// empty sequence is ignored.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`glyph_count`](Self::glyph_count).
+    pub fn add_sequence(&mut self, index: u32, seq: &[char]) {
+        assert!(index < self.glyph_count(), "glyph index out of range");
+        if seq.is_empty() {
+            return;
+        }
+        self.start_table();
+        let entry = &mut self.entries[index as usize];
+        match *seq {
+            [c] => entry.chars.push(c),
+            _ => entry.sequences.push(seq.to_vec()),
+        }
+    }
+
+    /// Unmap `c` from every glyph, returning whether it was mapped at all
+    pub fn remove_char(&mut self, c: char) -> bool {
+        let mut found = false;
+        for entry in &mut self.entries {
+            let len = entry.chars.len();
+            entry.chars.retain(|&x| x != c);
+            found |= entry.chars.len() != len;
+        }
+        found
+    }
+
+    /// Unmap the sequence `seq` from every glyph, returning whether it was mapped at all
+    ///
+    /// A sequence of one code point is removed as if by [`remove_char`](Self::remove_char).
+    pub fn remove_sequence(&mut self, seq: &[char]) -> bool {
+        if let [c] = *seq {
+            return self.remove_char(c);
+        }
+        let mut found = false;
+        for entry in &mut self.entries {
+            let len = entry.sequences.len();
+            entry.sequences.retain(|x| x != seq);
+            found |= entry.sequences.len() != len;
+        }
+        found
+    }
+
+    /// Unmap every code point and sequence from the `index`th glyph
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`glyph_count`](Self::glyph_count).
+    pub fn clear_mappings(&mut self, index: u32) {
+        let entry = &mut self.entries[index as usize];
+        entry.chars.clear();
+        entry.sequences.clear();
+    }
+
+    /// Whether glyphs are looked up through a Unicode table rather than by code point
+    #[inline]
+    pub(crate) fn has_unicode_table(&self) -> bool {
+        self.has_table
+    }
+
+    /// Look glyphs up through a Unicode table from now on, first mapping each existing glyph to
+    /// the code point it was found by
+    fn start_table(&mut self) {
+        if self.has_table {
+            return;
+        }
+        if !self.building {
+            for (index, entry) in self.entries.iter_mut().enumerate() {
+                entry.chars.extend(char::from_u32(index as u32));
+            }
+        }
+        self.has_table = true;
+    }
+}
+
+impl<Data: AsRef<[u8]>> From<&Font<Data>> for OwnedFont {
+    fn from(font: &Font<Data>) -> Self {
+        let mut result = OwnedFont::new(font.width(), font.height());
+        result.has_table = font.header.unicode.is_some();
+        for (_, glyph, mappings) in font.glyphs() {
+            result.bitmaps.extend_from_slice(glyph.data());
+            let mut entry = Entry::default();
+            // Fonts without a Unicode table are left without one
+            if font.header.unicode.is_some() {
+                for mapping in mappings {
+                    match mapping {
+                        Mapping::Char(c) => entry.chars.push(c),
+                        Mapping::Sequence(seq) => entry.sequences.push(seq.collect()),
+                    }
+                }
+            }
+            result.entries.push(entry);
+        }
+        result
+    }
+}
+
+impl OwnedFont {
+    /// Copy `font`, mapping each glyph of a font without a Unicode table to its code point
+    pub(crate) fn with_table<Data: AsRef<[u8]>>(font: &Font<Data>) -> Self {
+        let mut result = Self::from(font);
+        result.start_table();
+        result
+    }
+}
+
+/// A mutable view of a glyph in an [`OwnedFont`]
+///
+/// Returned by [`OwnedFont::glyph_mut`].
+pub struct GlyphMut<'a> {
+    data: &'a mut [u8],
+    width: u32,
+    height: u32,
+}
+
+impl GlyphMut<'_> {
+    /// Number of columns in the glyph
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Number of rows in the glyph
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The raw bitmap, in the layout of [`Glyph::data`]
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    /// The raw bitmap, in the layout of [`Glyph::data`], for modification
+    #[inline]
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    /// Get an iterator over the rows of the glyph
+    #[inline]
+    pub fn as_glyph(&self) -> Glyph<'_> {
+        Glyph {
+            data: self.data,
+            width: self.width as usize,
+        }
+    }
+
+    /// Whether the pixel in column `x` of row `y` is filled, if in bounds
+    #[inline]
+    pub fn pixel(&self, x: u32, y: u32) -> Option<bool> {
+        self.as_glyph().pixel(x, y)
+    }
+
+    /// Fill the pixel in column `x` of row `y`
+    ///
+    /// # Panics
+    ///
+    /// If the pixel is out of bounds.
+    #[inline]
+    pub fn set(&mut self, x: u32, y: u32) {
+        let (i, mask) = self.locate(x, y);
+        self.data[i] |= mask;
+    }
+
+    /// Empty the pixel in column `x` of row `y`
+    ///
+    /// # Panics
+    ///
+    /// If the pixel is out of bounds.
+    #[inline]
+    pub fn clear(&mut self, x: u32, y: u32) {
+        let (i, mask) = self.locate(x, y);
+        self.data[i] &= !mask;
+    }
+
+    /// Flip the pixel in column `x` of row `y`
+    ///
+    /// # Panics
+    ///
+    /// If the pixel is out of bounds.
+    #[inline]
+    pub fn toggle(&mut self, x: u32, y: u32) {
+        let (i, mask) = self.locate(x, y);
+        self.data[i] ^= mask;
+    }
+
+    /// Byte offset and bit mask of a pixel
+    #[inline]
+    fn locate(&self, x: u32, y: u32) -> (usize, u8) {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({x}, {y}) out of bounds of {}x{} glyph",
+            self.width,
+            self.height
+        );
+        let stride = self.width.div_ceil(8) as usize;
+        (y as usize * stride + (x as usize >> 3), 0x80 >> (x & 7))
+    }
+}
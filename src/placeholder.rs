@@ -0,0 +1,171 @@
+//! Synthesized glyphs showing the code point of a missing character
+
+use crate::{Font, Glyph};
+
+/// Columns in each embedded digit
+const DIGIT_WIDTH: u32 = 3;
+/// Rows in each embedded digit
+const DIGIT_HEIGHT: u32 = 5;
+
+/// Hexadecimal digits, one row per byte, the rightmost `DIGIT_WIDTH` bits significant
+const DIGITS: [[u8; DIGIT_HEIGHT as usize]; 16] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b111, 0b101, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b110, 0b101, 0b110],
+    [0b111, 0b100, 0b100, 0b100, 0b111],
+    [0b110, 0b101, 0b101, 0b101, 0b110],
+    [0b111, 0b100, 0b111, 0b100, 0b111],
+    [0b111, 0b100, 0b111, 0b100, 0b100],
+];
+
+impl<Data: AsRef<[u8]>> Font<Data> {
+    /// Synthesize a glyph showing the code point of `c`, for use when the font lacks it
+    ///
+    /// As in GNU Unifont, the glyph is a box containing the code point in hexadecimal, as two
+    /// rows of two digits, or of three beyond the Basic Multilingual Plane. The digits are
+    /// cropped from the font's own `0`-`9` and `A`-`F` glyphs if they fit, or else drawn from
+    /// a tiny embedded set of 3x5 digits. If the glyph is too small even for those, only the
+    /// box is drawn.
+    ///
+    /// # Panics
+    ///
+    /// If `buf` is shorter than [`glyph_size`](Self::glyph_size).
+    pub fn placeholder_glyph<'a>(&self, c: char, buf: &'a mut [u8]) -> Glyph<'a> {
+        let (width, height) = (self.width(), self.height());
+        let data = &mut buf[..self.glyph_size()];
+        data.fill(0);
+        let mut image = Image {
+            data: &mut *data,
+            stride: width.div_ceil(8) as usize,
+        };
+        for x in 0..width {
+            image.set(x, 0);
+            image.set(x, height - 1);
+        }
+        for y in 0..height {
+            image.set(0, y);
+            image.set(width - 1, y);
+        }
+
+        let (inner_width, inner_height) = (width.saturating_sub(2), height.saturating_sub(2));
+        let code = u32::from(c);
+        let columns = if code > 0xffff { 3 } else { 2 };
+        let digits = [self.own_digits(), Some(Digits::EMBEDDED)]
+            .into_iter()
+            .flatten()
+            .find_map(|digits| {
+                let gap = digits.gap(columns, inner_width)?;
+                (2 * digits.height < inner_height).then_some((digits, gap))
+            });
+        if let Some((digits, gap)) = digits {
+            let (digit_width, digit_height) = (digits.width, digits.height);
+            let left = 1 + (inner_width - (columns * digit_width + (columns - 1) * gap)) / 2;
+            let top = 1 + (inner_height - (2 * digit_height + 1)) / 2;
+            for i in 0..2 * columns {
+                let digit = (code >> (4 * (2 * columns - 1 - i))) & 0xf;
+                let x = left + (i % columns) * (digit_width + gap);
+                let y = top + (i / columns) * (digit_height + 1);
+                for dy in 0..digit_height {
+                    for dx in 0..digit_width {
+                        if digits.pixel(digit as usize, dx, dy) {
+                            image.set(x + dx, y + dy);
+                        }
+                    }
+                }
+            }
+        }
+        Glyph {
+            data,
+            width: width as usize,
+        }
+    }
+
+    /// The font's hexadecimal digits, cropped to the area any of them covers
+    fn own_digits(&self) -> Option<Digits<'_>> {
+        let mut glyphs = [const { None }; 16];
+        for (i, c) in ('0'..='9').chain('A'..='F').enumerate() {
+            glyphs[i] = Some(self.get_unicode(c)?);
+        }
+        let glyphs = glyphs.map(Option::unwrap);
+        let (mut left, mut top) = (u32::MAX, u32::MAX);
+        let (mut right, mut bottom) = (0, 0);
+        for glyph in &glyphs {
+            for (y, row) in glyph.clone().enumerate() {
+                for (x, _) in row.enumerate().filter(|&(_, filled)| filled) {
+                    left = left.min(x as u32);
+                    right = right.max(x as u32 + 1);
+                    top = top.min(y as u32);
+                    bottom = bottom.max(y as u32 + 1);
+                }
+            }
+        }
+        (left < right).then_some(Digits {
+            glyphs: Some(glyphs),
+            left,
+            top,
+            width: right - left,
+            height: bottom - top,
+        })
+    }
+}
+
+/// Bitmaps for the digits of a placeholder
+struct Digits<'a> {
+    /// Glyphs to crop the digits from, or `None` to use the embedded digits
+    glyphs: Option<[Glyph<'a>; 16]>,
+    left: u32,
+    top: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Digits<'_> {
+    const EMBEDDED: Self = Self {
+        glyphs: None,
+        left: 0,
+        top: 0,
+        width: DIGIT_WIDTH,
+        height: DIGIT_HEIGHT,
+    };
+
+    /// Space between columns of digits fitting `columns` of them in `width`, preferring one
+    /// pixel over none
+    fn gap(&self, columns: u32, width: u32) -> Option<u32> {
+        [1, 0]
+            .into_iter()
+            .find(|gap| columns * self.width + (columns - 1) * gap <= width)
+    }
+
+    #[inline]
+    fn pixel(&self, digit: usize, x: u32, y: u32) -> bool {
+        match self.glyphs {
+            None => DIGITS[digit][y as usize] & (1 << (DIGIT_WIDTH - 1 - x)) != 0,
+            Some(ref glyphs) => glyphs[digit]
+                .pixel(self.left + x, self.top + y)
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// A 1-bpp image in glyph layout
+struct Image<'a> {
+    data: &'a mut [u8],
+    stride: usize,
+}
+
+impl Image<'_> {
+    #[inline]
+    fn set(&mut self, x: u32, y: u32) {
+        let x = x as usize;
+        self.data[y as usize * self.stride + (x >> 3)] |= 0x80 >> (x & 7);
+    }
+}
@@ -0,0 +1,187 @@
+//! Drawing whole strings into bitmaps
+
+use crate::{Font, Glyph};
+
+/// Pixel layout of a [`Canvas`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// One bit per pixel, the most significant bit leftmost, with each row padded to a whole
+    /// number of bytes
+    Mono,
+    /// One byte per pixel
+    Gray8,
+}
+
+impl Format {
+    /// Number of bytes in each row of an image `width` pixels wide
+    #[inline]
+    pub fn stride(self, width: u32) -> usize {
+        match self {
+            Format::Mono => width.div_ceil(8) as usize,
+            Format::Gray8 => width as usize,
+        }
+    }
+
+    /// Number of bytes in an image of `width` by `height` pixels
+    #[inline]
+    pub fn buffer_len(self, width: u32, height: u32) -> usize {
+        self.stride(width) * height as usize
+    }
+}
+
+/// A bitmap to draw text into
+///
+/// Drawing sets the bits of filled pixels in [`Format::Mono`] images and sets filled pixels to
+/// `0xff` in [`Format::Gray8`] images. All other pixels are left untouched.
+pub struct Canvas<'a> {
+    data: &'a mut [u8],
+    format: Format,
+    width: u32,
+    height: u32,
+}
+
+impl<'a> Canvas<'a> {
+    /// Wrap `data` as an image of `width` by `height` pixels
+    ///
+    /// Returns `None` if `data` is shorter than [`Format::buffer_len`].
+    pub fn new(data: &'a mut [u8], format: Format, width: u32, height: u32) -> Option<Self> {
+        if data.len() < format.buffer_len(width, height) {
+            return None;
+        }
+        Some(Self {
+            data,
+            format,
+            width,
+            height,
+        })
+    }
+
+    /// Number of columns in the image
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Number of rows in the image
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The raw image data
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    /// Draw `glyph` with its top-left corner at (`x`, `y`), clipped to the image
+    pub fn draw(&mut self, glyph: Glyph<'_>, x: u32, y: u32) {
+        let stride = self.format.stride(self.width);
+        let cols = glyph.width().min(self.width.saturating_sub(x)) as usize;
+        let rows = self.height.saturating_sub(y) as usize;
+        for (dy, row) in glyph.take(rows).enumerate() {
+            let start = (y as usize + dy) * stride;
+            let out = &mut self.data[start..start + stride];
+            for (dx, _) in row.take(cols).enumerate().filter(|&(_, filled)| filled) {
+                let px = x as usize + dx;
+                match self.format {
+                    Format::Mono => out[px >> 3] |= 0x80 >> (px & 7),
+                    Format::Gray8 => out[px] = 0xff,
+                }
+            }
+        }
+    }
+}
+
+/// How to lay out text drawn by [`Font::render_str`]
+#[derive(Debug, Copy, Clone)]
+pub struct RenderOptions {
+    /// Pixels between adjacent glyphs
+    pub letter_spacing: u32,
+    /// Pixels between adjacent lines
+    pub line_spacing: u32,
+    /// Distance between tab stops, in glyphs
+    pub tab_width: u32,
+    /// Character drawn in place of those the font lacks, or `None` to leave a blank space
+    pub fallback: Option<char>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            letter_spacing: 0,
+            line_spacing: 0,
+            tab_width: 8,
+            fallback: Some('?'),
+        }
+    }
+}
+
+impl<Data: AsRef<[u8]>> Font<Data> {
+    /// Draw `text` into `canvas`, starting from its top-left corner
+    ///
+    /// Lines are broken at each `\n`, and `\t` advances to the next tab stop. Returns the width
+    /// and height of the text in pixels, as [`measure_str`](Self::measure_str).
+    pub fn render_str(
+        &self,
+        text: &str,
+        canvas: &mut Canvas<'_>,
+        options: &RenderOptions,
+    ) -> (u32, u32) {
+        let fallback = options.fallback.and_then(|c| self.get_unicode(c));
+        self.layout_str(text, options, |x, y, glyph| {
+            if let Some(glyph) = glyph.ok().or_else(|| fallback.clone()) {
+                canvas.draw(glyph, x, y);
+            }
+        })
+    }
+
+    /// Compute the width and height in pixels of `text` drawn by
+    /// [`render_str`](Self::render_str)
+    pub fn measure_str(&self, text: &str, options: &RenderOptions) -> (u32, u32) {
+        self.layout_str(text, options, |_, _, _| {})
+    }
+
+    /// Pass the position of each glyph of `text` to `draw`, or of each character the font lacks,
+    /// and return the text's size
+    pub(crate) fn layout_str<'a>(
+        &'a self,
+        text: &'a str,
+        options: &RenderOptions,
+        mut draw: impl FnMut(u32, u32, Result<Glyph<'a>, char>),
+    ) -> (u32, u32) {
+        let advance = self.width().saturating_add(options.letter_spacing);
+        let line_height = self.height().saturating_add(options.line_spacing);
+        let tab_width = options.tab_width.max(1);
+
+        let (mut width, mut lines) = (0, 0u32);
+        for line in text.split('\n') {
+            let y = lines.saturating_mul(line_height);
+            let mut col = 0;
+            for (i, segment) in line
+                .strip_suffix('\r')
+                .unwrap_or(line)
+                .split('\t')
+                .enumerate()
+            {
+                if i > 0 {
+                    col = (col / tab_width + 1).saturating_mul(tab_width);
+                }
+                for glyph in self.glyphs_for_str(segment) {
+                    draw(col.saturating_mul(advance), y, glyph);
+                    col = col.saturating_add(1);
+                }
+            }
+            if col > 0 {
+                width = width.max(
+                    (col - 1)
+                        .saturating_mul(advance)
+                        .saturating_add(self.width()),
+                );
+            }
+            lines += 1;
+        }
+        let height = (lines - 1).saturating_mul(line_height);
+        (width, height.saturating_add(self.height()))
+    }
+}
@@ -0,0 +1,181 @@
+//! Several fonts used together to cover more characters than any one of them
+
+use core::fmt;
+
+use crate::{Font, Glyph, PositionedGlyph};
+
+/// A list of fonts consulted in order, so that characters missing from one font are drawn with
+/// the next
+///
+/// Glyphs are positioned within a cell of [`width`](Self::width) by [`height`](Self::height)
+/// pixels.
+pub struct FontStack<'a, Data> {
+    fonts: &'a [Font<Data>],
+    width: u32,
+    height: u32,
+}
+
+// Manual impls to avoid requiring `Data: Clone`
+impl<Data> Clone for FontStack<'_, Data> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Data> Copy for FontStack<'_, Data> {}
+
+impl<'a, Data: AsRef<[u8]>> FontStack<'a, Data> {
+    /// Compose `fonts`, which must all have glyphs of the same size
+    pub fn new(fonts: &'a [Font<Data>]) -> Result<Self, StackError> {
+        let first = fonts.first().ok_or(StackError::Empty)?;
+        let (width, height) = (first.width(), first.height());
+        for (index, font) in fonts.iter().enumerate() {
+            if (font.width(), font.height()) != (width, height) {
+                return Err(StackError::SizeMismatch {
+                    index,
+                    width: font.width(),
+                    height: font.height(),
+                });
+            }
+        }
+        Ok(Self {
+            fonts,
+            width,
+            height,
+        })
+    }
+
+    /// Compose `fonts` of any size, centering the glyphs of smaller fonts in a cell large enough
+    /// for any of them
+    ///
+    /// Where glyphs can't be centered exactly, they're placed up and to the left.
+    pub fn centered(fonts: &'a [Font<Data>]) -> Result<Self, StackError> {
+        if fonts.is_empty() {
+            return Err(StackError::Empty);
+        }
+        Ok(Self {
+            fonts,
+            width: fonts.iter().map(|x| x.width()).max().unwrap(),
+            height: fonts.iter().map(|x| x.height()).max().unwrap(),
+        })
+    }
+
+    /// The fonts, in the order they're consulted
+    #[inline]
+    pub fn fonts(&self) -> &'a [Font<Data>] {
+        self.fonts
+    }
+
+    /// Number of columns in a cell
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Number of rows in a cell
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the glyph for `c` from the first font that has one, and its position within the cell
+    pub fn get(&self, c: char) -> Option<PositionedGlyph<'a>> {
+        self.fonts
+            .iter()
+            .find_map(|font| Some(self.position(font, font.get_unicode(c)?)))
+    }
+
+    /// Iterate over the glyphs needed to draw `text`, and their positions within each cell
+    ///
+    /// At each point in the text, the first font with a glyph for the next character is used, as
+    /// by [`Font::glyphs_for_str`]. Characters with no glyph in any font are yielded as errors.
+    pub fn glyphs_for_str(&self, text: &'a str) -> StackGlyphs<'a, Data> {
+        StackGlyphs { stack: *self, text }
+    }
+
+    fn position(&self, font: &Font<Data>, glyph: Glyph<'a>) -> PositionedGlyph<'a> {
+        PositionedGlyph {
+            x: (self.width - font.width()) / 2,
+            y: (self.height - font.height()) / 2,
+            glyph,
+        }
+    }
+}
+
+/// Iterator over the glyphs needed to draw a string with a [`FontStack`]
+///
+/// Returned by [`FontStack::glyphs_for_str`].
+pub struct StackGlyphs<'a, Data> {
+    stack: FontStack<'a, Data>,
+    text: &'a str,
+}
+
+// Manual impl to avoid requiring `Data: Clone`
+impl<Data> Clone for StackGlyphs<'_, Data> {
+    fn clone(&self) -> Self {
+        Self {
+            stack: self.stack,
+            text: self.text,
+        }
+    }
+}
+
+impl<'a, Data> StackGlyphs<'a, Data> {
+    /// The text not yet iterated through
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+}
+
+impl<'a, Data: AsRef<[u8]>> Iterator for StackGlyphs<'a, Data> {
+    type Item = Result<PositionedGlyph<'a>, char>;
+
+    fn next(&mut self) -> Option<Result<PositionedGlyph<'a>, char>> {
+        let c = self.text.chars().next()?;
+        let found = self.stack.fonts.iter().find_map(|font| {
+            let (len, index) = font.longest_match(self.text)?;
+            Some((len, self.stack.position(font, font.get_index(index)?)))
+        });
+        let (len, glyph) = match found {
+            Some((len, glyph)) => (len, Ok(glyph)),
+            None => (c.len_utf8(), Err(c)),
+        };
+        self.text = &self.text[len..];
+        Some(glyph)
+    }
+}
+
+/// Why fonts might not be composable into a [`FontStack`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StackError {
+    /// No fonts were supplied
+    Empty,
+    /// A font's glyphs differ in size from those of the first font
+    SizeMismatch {
+        /// Position of the offending font in the list
+        index: usize,
+        /// Number of columns in the font's glyphs
+        width: u32,
+        /// Number of rows in the font's glyphs
+        height: u32,
+    },
+}
+
+impl fmt::Display for StackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            StackError::Empty => f.write_str("no fonts to stack"),
+            StackError::SizeMismatch {
+                index,
+                width,
+                height,
+            } => write!(
+                f,
+                "font {index} has {width}x{height} glyphs, unlike the first font"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for StackError {}
@@ -0,0 +1,90 @@
+//! Reducing a font to the glyphs needed for some text
+
+use alloc::{vec, vec::Vec};
+
+use crate::{owned::Entry, Font, OwnedFont};
+
+/// How to arrange the glyphs kept by [`Font::subset`]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SubsetOptions {
+    /// Whether to place the glyph for each ASCII character at the index equal to its code point,
+    /// as expected by software that ignores the Unicode table
+    ///
+    /// Unused positions below the highest such index are filled by other glyphs, or by blank
+    /// glyphs once those run out.
+    pub preserve_ascii: bool,
+}
+
+impl<Data: AsRef<[u8]>> Font<Data> {
+    /// Copy just the glyphs needed to draw `text`, along with all of their mappings
+    ///
+    /// `text` is matched against the font as by [`glyphs_for_str`](Self::glyphs_for_str), so a
+    /// sequence keeps its own glyph rather than those of its code points, and characters the font
+    /// lacks are ignored. Kept glyphs are numbered from 0 and retain their relative order, except
+    /// where [`SubsetOptions::preserve_ascii`] moves them to or around ASCII positions. The result
+    /// always has a Unicode table, even if this font is indexed by code point.
+    pub fn subset(&self, text: &str, options: &SubsetOptions) -> OwnedFont {
+        let source = OwnedFont::with_table(self);
+        let mut kept = vec![false; self.glyph_count() as usize];
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            let len = match self.longest_match(rest) {
+                Some((len, index)) => {
+                    kept[index as usize] = true;
+                    len
+                }
+                None => c.len_utf8(),
+            };
+            rest = &rest[len..];
+        }
+
+        // Position of each kept glyph in the result, with `None` for blanks
+        let mut order = Vec::<Option<usize>>::new();
+        let mut others = Vec::new();
+        for (index, _) in kept.iter().enumerate().filter(|&(_, &x)| x) {
+            let ascii = match options.preserve_ascii {
+                true => source.entries[index]
+                    .chars
+                    .iter()
+                    .copied()
+                    .filter(char::is_ascii)
+                    .min(),
+                false => None,
+            };
+            match ascii.map(|c| c as usize) {
+                Some(c) if order.get(c).is_none_or(Option::is_none) => {
+                    if order.len() <= c {
+                        order.resize(c + 1, None);
+                    }
+                    order[c] = Some(index);
+                }
+                _ => others.push(index),
+            }
+        }
+        let mut others = others.into_iter();
+        for slot in order.iter_mut().filter(|x| x.is_none()) {
+            *slot = others.next();
+        }
+        order.extend(others.map(Some));
+
+        let mut result = OwnedFont::new(self.width(), self.height());
+        result.has_table = true;
+        let size = result.glyph_size();
+        for slot in order {
+            match slot {
+                Some(index) => {
+                    let start = index * size;
+                    result
+                        .bitmaps
+                        .extend_from_slice(&source.bitmaps[start..start + size]);
+                    result.entries.push(source.entries[index].clone());
+                }
+                None => {
+                    result.bitmaps.resize(result.bitmaps.len() + size, 0);
+                    result.entries.push(Entry::default());
+                }
+            }
+        }
+        result
+    }
+}
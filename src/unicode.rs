@@ -0,0 +1,296 @@
+//! The optional table mapping Unicode code points to glyphs
+
+use core::{slice, str};
+
+use crate::ParseError;
+
+/// Ends the list of code points describing a glyph
+const TERMINATOR: u16 = 0xffff;
+/// Introduces a sequence of code points which together describe a glyph
+const SEQUENCE_START: u16 = 0xfffe;
+
+/// How code points are stored in a Unicode table
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    /// PSF2: UTF-8, with the markers stored as single bytes
+    Utf8,
+    /// PSF1: little-endian UCS-2
+    Ucs2,
+}
+
+impl Encoding {
+    /// Size in bytes of a marker
+    #[inline]
+    fn unit(self) -> usize {
+        match self {
+            Encoding::Utf8 => 1,
+            Encoding::Ucs2 => 2,
+        }
+    }
+
+    /// Byte offset of the first occurrence of `marker` in `data`
+    #[inline]
+    fn find(self, data: &[u8], marker: u16) -> Option<usize> {
+        match self {
+            Encoding::Utf8 => data.iter().position(|&x| x == marker as u8),
+            Encoding::Ucs2 => data
+                .chunks_exact(2)
+                .position(|x| x == marker.to_le_bytes())
+                .map(|i| i * 2),
+        }
+    }
+
+    /// Check that `data` contains only well-formed code points, or find the first malformed one
+    fn validate(self, data: &[u8]) -> Result<(), usize> {
+        match self {
+            Encoding::Utf8 => {
+                str::from_utf8(data).map_err(|e| e.valid_up_to())?;
+            }
+            Encoding::Ucs2 => {
+                for (i, x) in data.chunks_exact(2).enumerate() {
+                    if char::from_u32(u16::from_le_bytes([x[0], x[1]]).into()).is_none() {
+                        return Err(i * 2);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check that `data` contains `length` well-formed entries starting at `offset`
+pub(crate) fn validate(
+    data: &[u8],
+    mut offset: usize,
+    length: u32,
+    encoding: Encoding,
+) -> Result<(), ParseError> {
+    for _ in 0..length {
+        let end = offset
+            + encoding.find(&data[offset..], TERMINATOR).ok_or_else(|| {
+                let available = data.len() - offset;
+                ParseError::unexpected_end(data, offset, available + encoding.unit())
+            })?;
+        while offset < end {
+            let part_end = encoding
+                .find(&data[offset..end], SEQUENCE_START)
+                .map_or(end, |x| offset + x);
+            encoding
+                .validate(&data[offset..part_end])
+                .map_err(|x| ParseError::BadUnicodeTable { offset: offset + x })?;
+            offset = part_end + encoding.unit();
+        }
+        offset = end + encoding.unit();
+    }
+    Ok(())
+}
+
+/// Iterator over the entries of a validated Unicode table, one per glyph
+#[derive(Clone)]
+pub(crate) struct Entries<'a> {
+    data: &'a [u8],
+    remaining: u32,
+    encoding: Encoding,
+}
+
+impl<'a> Entries<'a> {
+    /// `data` must have been accepted by [`validate`] with the same `length` and `encoding`
+    pub(crate) fn new(data: &'a [u8], length: u32, encoding: Encoding) -> Self {
+        Self {
+            data,
+            remaining: length,
+            encoding,
+        }
+    }
+}
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = Entry<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Entry<'a>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let end = self.encoding.find(self.data, TERMINATOR)?;
+        let (entry, rest) = self.data.split_at(end);
+        self.data = &rest[self.encoding.unit()..];
+        Some(Entry {
+            data: entry,
+            encoding: self.encoding,
+        })
+    }
+}
+
+/// The code points describing a single glyph
+#[derive(Clone)]
+pub(crate) struct Entry<'a> {
+    data: &'a [u8],
+    encoding: Encoding,
+}
+
+impl<'a> Entry<'a> {
+    /// Individual code points which map to this glyph
+    pub(crate) fn chars(&self) -> Chars<'a> {
+        let end = self
+            .encoding
+            .find(self.data, SEQUENCE_START)
+            .unwrap_or(self.data.len());
+        Chars::new(&self.data[..end], self.encoding)
+    }
+
+    /// All code points and sequences which map to this glyph
+    pub(crate) fn mappings(&self) -> Mappings<'a> {
+        Mappings(MappingsInner::Table {
+            chars: self.chars(),
+            sequences: self.sequences(),
+        })
+    }
+
+    /// Sequences of code points which together map to this glyph
+    pub(crate) fn sequences(&self) -> Sequences<'a> {
+        let data = self
+            .encoding
+            .find(self.data, SEQUENCE_START)
+            .map(|start| &self.data[start + self.encoding.unit()..]);
+        Sequences {
+            data,
+            encoding: self.encoding,
+        }
+    }
+}
+
+/// Iterator over the sequences of code points in an [`Entry`]
+#[derive(Debug, Clone)]
+pub(crate) struct Sequences<'a> {
+    data: Option<&'a [u8]>,
+    encoding: Encoding,
+}
+
+impl<'a> Iterator for Sequences<'a> {
+    type Item = Chars<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Chars<'a>> {
+        let data = self.data?;
+        let part = match self.encoding.find(data, SEQUENCE_START) {
+            Some(end) => {
+                self.data = Some(&data[end + self.encoding.unit()..]);
+                &data[..end]
+            }
+            None => {
+                self.data = None;
+                data
+            }
+        };
+        Some(Chars::new(part, self.encoding))
+    }
+}
+
+/// Iterator over validated code points in either encoding
+#[derive(Debug, Clone)]
+pub(crate) enum Chars<'a> {
+    Utf8(str::Chars<'a>),
+    Ucs2(slice::ChunksExact<'a, u8>),
+}
+
+impl<'a> Chars<'a> {
+    fn new(data: &'a [u8], encoding: Encoding) -> Self {
+        match encoding {
+            // Safety: checked by `validate`
+            Encoding::Utf8 => Chars::Utf8(unsafe { str::from_utf8_unchecked(data) }.chars()),
+            Encoding::Ucs2 => Chars::Ucs2(data.chunks_exact(2)),
+        }
+    }
+}
+
+impl Iterator for Chars<'_> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        match *self {
+            Chars::Utf8(ref mut x) => x.next(),
+            Chars::Ucs2(ref mut x) => {
+                let x = x.next()?;
+                char::from_u32(u16::from_le_bytes([x[0], x[1]]).into())
+            }
+        }
+    }
+}
+
+/// Iterator over the code points and sequences of code points that map to a glyph
+///
+/// Returned by [`Font::glyphs`](crate::Font::glyphs).
+#[derive(Debug, Clone)]
+pub struct Mappings<'a>(MappingsInner<'a>);
+
+#[derive(Debug, Clone)]
+enum MappingsInner<'a> {
+    /// The font has no Unicode table, so glyphs are indexed by code point
+    Implicit(Option<char>),
+    Table {
+        chars: Chars<'a>,
+        sequences: Sequences<'a>,
+    },
+}
+
+impl Mappings<'_> {
+    /// Mappings for the `index`th glyph of a font without a Unicode table
+    pub(crate) fn implicit(index: u32) -> Self {
+        Mappings(MappingsInner::Implicit(char::from_u32(index)))
+    }
+}
+
+impl<'a> Iterator for Mappings<'a> {
+    type Item = Mapping<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Mapping<'a>> {
+        match self.0 {
+            MappingsInner::Implicit(ref mut c) => c.take().map(Mapping::Char),
+            MappingsInner::Table {
+                ref mut chars,
+                ref mut sequences,
+            } => chars
+                .next()
+                .map(Mapping::Char)
+                .or_else(|| sequences.next().map(|x| Mapping::Sequence(Sequence(x)))),
+        }
+    }
+}
+
+/// A code point or sequence of code points that maps to a glyph
+#[derive(Debug, Clone)]
+pub enum Mapping<'a> {
+    /// A single code point
+    Char(char),
+    /// Several code points which together map to one glyph, e.g. a letter followed by combining
+    /// accents
+    Sequence(Sequence<'a>),
+}
+
+/// Iterator over the code points of a [`Mapping::Sequence`]
+#[derive(Debug, Clone)]
+pub struct Sequence<'a>(Chars<'a>);
+
+impl Iterator for Sequence<'_> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        self.0.next()
+    }
+}
+
+/// Number of bytes of `text` matched by `seq`, if `text` begins with `seq`
+pub(crate) fn match_prefix(seq: Chars<'_>, text: &str) -> Option<usize> {
+    let mut rest = text.chars();
+    for c in seq {
+        if rest.next() != Some(c) {
+            return None;
+        }
+    }
+    Some(text.len() - rest.as_str().len())
+}
@@ -0,0 +1,309 @@
+//! Constructing fonts and serializing them to PSF
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    Font, OwnedFont, PSF1_MAGIC, PSF1_MODE512, PSF1_MODEHASSEQ, PSF1_MODEHASTAB,
+    PSF2_HAS_UNICODE_TABLE, PSF2_MAGIC,
+};
+
+/// Ends the list of code points describing a glyph in a PSF1 Unicode table
+const PSF1_TERMINATOR: u16 = 0xffff;
+/// Introduces a sequence of code points in a PSF1 Unicode table
+const PSF1_SEQUENCE_START: u16 = 0xfffe;
+/// Ends the list of code points describing a glyph in a PSF2 Unicode table
+const PSF2_TERMINATOR: u8 = 0xff;
+/// Introduces a sequence of code points in a PSF2 Unicode table
+const PSF2_SEQUENCE_START: u8 = 0xfe;
+
+/// Assembles glyphs and their Unicode mappings into a new font
+///
+/// A Unicode table is written only if some glyph has a mapping. Otherwise, the font is taken to be
+/// indexed by code point, as described at [`Font::get_unicode`]. For editing after glyphs are
+/// added, convert to an [`OwnedFont`] with [`into_owned`](Self::into_owned).
+#[derive(Debug, Clone)]
+pub struct FontBuilder {
+    font: OwnedFont,
+}
+
+impl FontBuilder {
+    /// Start a font with no glyphs, each of which will be `width` by `height` pixels
+    pub fn new(width: u32, height: u32) -> Self {
+        let mut font = OwnedFont::new(width, height);
+        font.building = true;
+        Self { font }
+    }
+
+    /// Number of columns in a glyph
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.font.width()
+    }
+
+    /// Number of rows in a glyph
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.font.height()
+    }
+
+    /// Number of bytes in the bitmap of a glyph
+    ///
+    /// Each of [`height`](Self::height) rows is padded to a whole number of bytes, the most
+    /// significant bit leftmost, as returned by [`Glyph::data`](crate::Glyph::data).
+    #[inline]
+    pub fn glyph_size(&self) -> usize {
+        self.font.glyph_size()
+    }
+
+    /// Number of glyphs added so far
+    #[inline]
+    pub fn glyph_count(&self) -> u32 {
+        self.font.glyph_count()
+    }
+
+    /// Add a glyph drawn by `bitmap` to which each of `chars` maps, returning its index
+    pub fn push_glyph(&mut self, bitmap: &[u8], chars: &[char]) -> Result<u32, WriteError> {
+        self.font.push_glyph(bitmap, chars)
+    }
+
+    /// Map `c` to the `index`th glyph
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`glyph_count`](Self::glyph_count).
+    pub fn add_char(&mut self, index: u32, c: char) {
+        self.font.add_char(index, c);
+    }
+
+    /// Map a sequence of code points, e.g. a letter followed by combining accents, to the
+    /// `index`th glyph
+    ///
+    /// A sequence of one code point is added as if by [`add_char`](Self::add_char), and an
+    /// empty sequence is ignored.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`glyph_count`](Self::glyph_count).
+    pub fn add_sequence(&mut self, index: u32, seq: &[char]) {
+        self.font.add_sequence(index, seq);
+    }
+
+    /// Serialize the font as PSF2
+    pub fn to_psf2(&self) -> Result<Vec<u8>, WriteError> {
+        self.font.to_psf2()
+    }
+
+    /// Serialize the font as PSF1, as by [`OwnedFont::to_psf1`]
+    pub fn to_psf1(&self) -> Result<Vec<u8>, WriteError> {
+        self.font.to_psf1()
+    }
+
+    /// Serialize the font as PSF2 and parse the result
+    pub fn build(&self) -> Result<Font<Vec<u8>>, WriteError> {
+        self.font.build()
+    }
+
+    /// Finish building, yielding a font that can be further edited
+    pub fn into_owned(mut self) -> OwnedFont {
+        self.font.building = false;
+        self.font
+    }
+}
+
+impl<Data: AsRef<[u8]>> From<&Font<Data>> for FontBuilder {
+    fn from(font: &Font<Data>) -> Self {
+        let mut font = OwnedFont::from(font);
+        font.building = true;
+        Self { font }
+    }
+}
+
+impl OwnedFont {
+    /// Check that the glyph dimensions can be stored
+    fn check_dimensions(&self) -> Result<u32, WriteError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(WriteError::ZeroDimension {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        u32::try_from(self.glyph_size()).map_err(|_| WriteError::TooLarge)
+    }
+
+    /// Serialize the font as PSF2
+    pub fn to_psf2(&self) -> Result<Vec<u8>, WriteError> {
+        let charsize = self.check_dimensions()?;
+        let unicode = self.has_unicode_table();
+        let mut out = Vec::with_capacity(8 * 4 + self.bitmaps.len());
+        out.extend_from_slice(&PSF2_MAGIC);
+        let flags = if unicode { PSF2_HAS_UNICODE_TABLE } else { 0 };
+        let fields = [0, 8 * 4, flags, self.glyph_count(), charsize];
+        for field in fields.into_iter().chain([self.height, self.width]) {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+        out.extend_from_slice(&self.bitmaps);
+        if unicode {
+            let mut buf = [0; 4];
+            for entry in &self.entries {
+                for &c in &entry.chars {
+                    out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                for seq in &entry.sequences {
+                    out.push(PSF2_SEQUENCE_START);
+                    for &c in seq {
+                        out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                    }
+                }
+                out.push(PSF2_TERMINATOR);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Serialize the font as PSF1, as understood by older Linux kernels and bootloaders
+    ///
+    /// PSF1 glyphs are always 8 pixels wide, so narrower glyphs are padded on the right. The glyph
+    /// count is padded with blank glyphs to 256 or 512, and the Unicode table can hold only code
+    /// points in the Basic Multilingual Plane.
+    pub fn to_psf1(&self) -> Result<Vec<u8>, WriteError> {
+        let charsize = self.check_dimensions()?;
+        if self.width > 8 {
+            return Err(WriteError::TooWide { width: self.width });
+        }
+        let charsize = u8::try_from(charsize).map_err(|_| WriteError::TooLarge)?;
+        let count = self.glyph_count();
+        let (mut mode, length) = match count {
+            0..=256 => (0, 256),
+            257..=512 => (PSF1_MODE512, 512),
+            _ => return Err(WriteError::TooManyGlyphs { count }),
+        };
+        let unicode = self.has_unicode_table();
+        if unicode {
+            mode |= PSF1_MODEHASTAB;
+        }
+        if self.entries.iter().any(|x| !x.sequences.is_empty()) {
+            mode |= PSF1_MODEHASSEQ;
+        }
+
+        let mut out = Vec::with_capacity(4 + length * usize::from(charsize));
+        out.extend_from_slice(&PSF1_MAGIC);
+        out.extend_from_slice(&[mode, charsize]);
+        // Rows are a single byte, whose bits past the glyph's width become visible in PSF1
+        let mask = 0xff_u8 << (8 - self.width);
+        out.extend(self.bitmaps.iter().map(|&x| x & mask));
+        out.resize(4 + length * usize::from(charsize), 0);
+        if unicode {
+            let mut push = |c: u16| out.extend_from_slice(&c.to_le_bytes());
+            for entry in &self.entries {
+                for &c in &entry.chars {
+                    push(ucs2(c)?);
+                }
+                for seq in &entry.sequences {
+                    push(PSF1_SEQUENCE_START);
+                    for &c in seq {
+                        push(ucs2(c)?);
+                    }
+                }
+                push(PSF1_TERMINATOR);
+            }
+            for _ in self.entries.len()..length {
+                push(PSF1_TERMINATOR);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Serialize the font as PSF2 and parse the result
+    pub fn build(&self) -> Result<Font<Vec<u8>>, WriteError> {
+        let data = self.to_psf2()?;
+        Ok(Font::new(data).expect("serialized font should be valid"))
+    }
+}
+
+impl<Data: AsRef<[u8]>> Font<Data> {
+    /// Serialize the font as PSF1, as by [`OwnedFont::to_psf1`]
+    pub fn to_psf1(&self) -> Result<Vec<u8>, WriteError> {
+        OwnedFont::from(self).to_psf1()
+    }
+
+    /// Serialize the font as PSF2
+    pub fn to_psf2(&self) -> Result<Vec<u8>, WriteError> {
+        OwnedFont::from(self).to_psf2()
+    }
+}
+
+/// Encode `c` for a PSF1 Unicode table
+fn ucs2(c: char) -> Result<u16, WriteError> {
+    match u16::try_from(u32::from(c)) {
+        Ok(x) if x != PSF1_TERMINATOR && x != PSF1_SEQUENCE_START => Ok(x),
+        _ => Err(WriteError::Unencodable { c }),
+    }
+}
+
+/// Why a font might not be serializable
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WriteError {
+    /// A glyph bitmap was the wrong size for the font's dimensions
+    BitmapSize {
+        /// Number of bytes needed
+        expected: usize,
+        /// Number of bytes supplied
+        actual: usize,
+    },
+    /// Glyphs have no rows or no columns
+    ZeroDimension {
+        /// Number of columns in a glyph
+        width: u32,
+        /// Number of rows in a glyph
+        height: u32,
+    },
+    /// Glyphs are too large for their size to be stored
+    TooLarge,
+    /// PSF1 glyphs can be at most 8 pixels wide
+    TooWide {
+        /// Number of columns in a glyph
+        width: u32,
+    },
+    /// PSF1 fonts can have at most 512 glyphs
+    TooManyGlyphs {
+        /// Number of glyphs in the font
+        count: u32,
+    },
+    /// A code point can't be stored in a PSF1 Unicode table, which is limited to the Basic
+    /// Multilingual Plane
+    Unencodable {
+        /// The code point
+        c: char,
+    },
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            WriteError::BitmapSize { expected, actual } => write!(
+                f,
+                "glyph bitmap is {actual} bytes, but {expected} are needed"
+            ),
+            WriteError::ZeroDimension { width, height } => {
+                write!(f, "glyph dimensions {width}x{height} are empty")
+            }
+            WriteError::TooLarge => f.write_str("glyphs too large to store"),
+            WriteError::TooWide { width } => {
+                write!(f, "glyphs {width} pixels wide exceed the PSF1 limit of 8")
+            }
+            WriteError::TooManyGlyphs { count } => {
+                write!(f, "{count} glyphs exceed the PSF1 limit of 512")
+            }
+            WriteError::Unencodable { c } => {
+                write!(
+                    f,
+                    "code point U+{:04X} can't be stored in PSF1",
+                    u32::from(c)
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for WriteError {}
@@ -0,0 +1,19 @@
+//! Fixtures shared between test files
+
+/// A PSF2 font of `width` by `height` glyphs drawn by the concatenated `bitmaps`, with a Unicode
+/// table of one raw entry per glyph if `table` is given
+pub fn psf2(width: u32, height: u32, bitmaps: &[u8], table: Option<&[&[u8]]>) -> Vec<u8> {
+    let charsize = height * width.div_ceil(8);
+    let length = bitmaps.len() as u32 / charsize;
+    let flags = table.is_some() as u32;
+    let mut data = vec![0x72, 0xb5, 0x4a, 0x86];
+    for field in [0, 32, flags, length, charsize, height, width] {
+        data.extend_from_slice(&u32::to_le_bytes(field));
+    }
+    data.extend_from_slice(bitmaps);
+    for entry in table.into_iter().flatten() {
+        data.extend_from_slice(entry);
+        data.push(0xff);
+    }
+    data
+}
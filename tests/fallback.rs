@@ -0,0 +1,154 @@
+mod common;
+
+use psf2::{Canvas, Fallback, Font, Format, RenderOptions};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+
+/// Render `glyph` as ASCII art
+fn art(glyph: psf2::Glyph<'_>) -> Vec<String> {
+    glyph
+        .map(|row| row.map(|x| if x { '#' } else { '.' }).collect())
+        .collect()
+}
+
+#[test]
+fn chain() {
+    let font = Font::new(FONT).unwrap();
+    let fallback = Fallback::new(&font);
+    let mut buf = vec![0; fallback.buffer_len()];
+    assert_eq!(buf.len(), 12);
+    let data = |c| font.get_unicode(c).unwrap().data();
+
+    assert_eq!(fallback.get('A', &mut buf).unwrap().data(), data('A'));
+    // No U+FFFD in this font, so '?' is next
+    assert_eq!(
+        fallback.get('\u{1f600}', &mut buf).unwrap().data(),
+        data('?')
+    );
+    let custom = fallback.with_replacements(&['~', '?']);
+    assert_eq!(custom.get('\u{1f600}', &mut buf).unwrap().data(), data('~'));
+
+    // Too small for digits, so just a box
+    let boxed = fallback.with_replacements(&[]);
+    assert_eq!(
+        art(boxed.get('\u{1f600}', &mut buf).unwrap()),
+        [
+            "######", "#....#", "#....#", "#....#", "#....#", "#....#", "#....#", "#....#",
+            "#....#", "#....#", "#....#", "######",
+        ]
+    );
+    assert!(boxed.get('\u{1f600}', &mut [0; 11]).is_none());
+    assert!(boxed
+        .with_placeholder(false)
+        .get('\u{1f600}', &mut buf)
+        .is_none());
+}
+
+#[test]
+fn placeholder() {
+    // Entirely filled, so too large to crop digits from
+    let data = common::psf2(16, 16, &[0xff; 32], Some(&[b"x"]));
+    let font = Font::new(&data[..]).unwrap();
+    let fallback = Fallback::new(&font);
+    let mut buf = vec![0; fallback.buffer_len()];
+    assert_eq!(fallback.get('x', &mut buf).unwrap().data(), &[0xff; 32]);
+    assert_eq!(
+        art(fallback.get('A', &mut buf).unwrap()),
+        [
+            "################",
+            "#..............#",
+            "#...###.###....#",
+            "#...#.#.#.#....#",
+            "#...#.#.#.#....#",
+            "#...#.#.#.#....#",
+            "#...###.###....#",
+            "#..............#",
+            "#...#.#..#.....#",
+            "#...#.#.##.....#",
+            "#...###..#.....#",
+            "#.....#..#.....#",
+            "#.....#.###....#",
+            "#..............#",
+            "#..............#",
+            "################",
+        ]
+    );
+    // Code points beyond the Basic Multilingual Plane need six digits
+    assert_eq!(
+        art(fallback.get('\u{1f600}', &mut buf).unwrap()),
+        [
+            "################",
+            "#..............#",
+            "#.###..#..###..#",
+            "#.#.#.##..#....#",
+            "#.#.#..#..###..#",
+            "#.#.#..#..#....#",
+            "#.###.###.#....#",
+            "#..............#",
+            "#.###.###.###..#",
+            "#.#...#.#.#.#..#",
+            "#.###.#.#.#.#..#",
+            "#.#.#.#.#.#.#..#",
+            "#.###.###.###..#",
+            "#..............#",
+            "#..............#",
+            "################",
+        ]
+    );
+}
+
+#[test]
+fn render() {
+    let font = Font::new(FONT).unwrap();
+    let fallback = Fallback::new(&font).with_replacements(&[]);
+    let mut scratch = vec![0; fallback.buffer_len()];
+    let mut buf = vec![0; Format::Gray8.buffer_len(12, 12)];
+    let mut canvas = Canvas::new(&mut buf, Format::Gray8, 12, 12).unwrap();
+    let options = RenderOptions::default();
+    let size = fallback.render_str("A\u{1f600}", &mut canvas, &options, &mut scratch);
+    assert_eq!(size, (12, 12));
+    // The placeholder's box fills the second cell
+    for y in 0..12 {
+        assert_eq!(buf[y * 12 + 6], 0xff);
+        assert_eq!(buf[y * 12 + 11], 0xff);
+    }
+    assert_eq!(buf[6 * 12 + 8], 0);
+}
+
+#[test]
+fn font_digits() {
+    // Digits drawn as distinct patterns within a 4x6 area
+    let digit = |i: usize| {
+        let mut bitmap = vec![0; 24 * 3];
+        for y in 3..9 {
+            for x in 4..8 {
+                if (x + y + i).is_multiple_of(3) || (x, y) == (4, 3) || (x, y) == (7, 8) {
+                    bitmap[y * 3 + x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        bitmap
+    };
+    let bitmaps = (0..16).flat_map(digit).collect::<Vec<_>>();
+    let digits = b"0123456789ABCDEF";
+    let table = digits.chunks(1).collect::<Vec<_>>();
+    let data = common::psf2(24, 24, &bitmaps, Some(&table));
+    let font = Font::new(&data[..]).unwrap();
+    let mut buf = vec![0; font.glyph_size()];
+    let glyph = font.placeholder_glyph('\u{1b2c}', &mut buf);
+    for (i, digit) in [1, 0xb, 2, 0xc].into_iter().enumerate() {
+        let expected = font.get_index(digit).unwrap();
+        let (left, top) = (7 + (i as u32 % 2) * 5, 5 + (i as u32 / 2) * 7);
+        for y in 0..6 {
+            for x in 0..4 {
+                assert_eq!(
+                    glyph.pixel(left + x, top + y),
+                    expected.pixel(4 + x, 3 + y),
+                    "digit {i} at ({x}, {y})"
+                );
+            }
+        }
+    }
+    assert_eq!(glyph.pixel(6, 5), Some(false));
+    assert_eq!(glyph.pixel(0, 5), Some(true));
+}
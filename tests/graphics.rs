@@ -0,0 +1,137 @@
+#![cfg(feature = "embedded-graphics")]
+
+use embedded_graphics::{
+    mock_display::MockDisplay,
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextRenderer},
+        Baseline, DecorationColor, Text,
+    },
+};
+use psf2::{Font, PsfTextStyle};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+
+/// Check that `display` contains `c` with its top-left corner at `origin`, drawn over `bg`
+fn assert_glyph_at(
+    font: &Font<&[u8]>,
+    display: &MockDisplay<BinaryColor>,
+    c: char,
+    origin: Point,
+    bg: Option<BinaryColor>,
+) {
+    let glyph = font.get_unicode(c).unwrap();
+    for (y, row) in glyph.enumerate() {
+        for (x, filled) in row.enumerate() {
+            let expected = if filled { Some(BinaryColor::On) } else { bg };
+            let p = origin + Point::new(x as i32, y as i32);
+            assert_eq!(display.get_pixel(p), expected, "{c:?} at {p:?}");
+        }
+    }
+}
+
+#[test]
+fn metrics() {
+    let font = Font::new(FONT).unwrap();
+    let style = PsfTextStyle::new(&font, BinaryColor::On);
+    assert_eq!(
+        (style.baseline, style.underline, style.strikethrough),
+        (8, 9, 5)
+    );
+    assert_eq!(style.line_height(), 12);
+    let metrics = style.measure_string("ab", Point::new(3, 20), Baseline::Alphabetic);
+    assert_eq!(
+        metrics.bounding_box,
+        Rectangle::new(Point::new(3, 12), Size::new(12, 12))
+    );
+    assert_eq!(metrics.next_position, Point::new(15, 20));
+}
+
+#[test]
+fn text() {
+    let font = Font::new(FONT).unwrap();
+    let mut style = PsfTextStyle::new(&font, BinaryColor::On);
+    style.letter_spacing = 1;
+    let mut display = MockDisplay::new();
+    let next = Text::with_baseline("AB\ng", Point::new(1, 2), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+    assert_eq!(next, Point::new(7, 14));
+    assert_glyph_at(&font, &display, 'A', Point::new(1, 2), None);
+    assert_glyph_at(&font, &display, 'B', Point::new(8, 2), None);
+    assert_glyph_at(&font, &display, 'g', Point::new(1, 14), None);
+
+    // Alphabetic baseline by default
+    let mut display = MockDisplay::new();
+    Text::new("A", Point::new(0, 8), style)
+        .draw(&mut display)
+        .unwrap();
+    assert_glyph_at(&font, &display, 'A', Point::new(0, 0), None);
+}
+
+#[test]
+fn decorations() {
+    let font = Font::new(FONT).unwrap();
+    let mut style = PsfTextStyle::new(&font, BinaryColor::On);
+    style.background_color = Some(BinaryColor::Off);
+    style.set_underline_color(DecorationColor::TextColor);
+    let mut display = MockDisplay::new();
+    display.set_allow_overdraw(true);
+    Text::with_baseline("H\u{1f600}", Point::zero(), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+    // Every pixel of the cell is drawn, with the underline on top
+    for x in 0..12 {
+        for y in 0..12 {
+            let expected = if y == 9 {
+                Some(BinaryColor::On)
+            } else {
+                font.get_unicode(if x < 6 { 'H' } else { '?' })
+                    .unwrap()
+                    .pixel(x % 6, y)
+                    .map(BinaryColor::from)
+            };
+            let p = Point::new(x as i32, y as i32);
+            assert_eq!(display.get_pixel(p), expected, "{p:?}");
+        }
+    }
+
+    let mut style = PsfTextStyle::new(&font, BinaryColor::On);
+    style.text_color = None;
+    style.fallback = None;
+    style.set_strikethrough_color(DecorationColor::Custom(BinaryColor::On));
+    let mut display = MockDisplay::new();
+    Text::with_baseline("\u{1f600}", Point::zero(), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+    let mut expected = MockDisplay::new();
+    expected.set_pixels((0..6).map(|x| Point::new(x, 5)), Some(BinaryColor::On));
+    display.assert_eq(&expected);
+}
+
+#[test]
+fn huge_spacing() {
+    let font = Font::new(FONT).unwrap();
+    let mut style = PsfTextStyle::new(&font, BinaryColor::On);
+    style.letter_spacing = u32::MAX;
+    let metrics = style.measure_string("ab", Point::zero(), Baseline::Top);
+    assert_eq!(metrics.bounding_box.size, Size::new(u32::MAX, 12));
+    assert_eq!(metrics.next_position, Point::new(i32::MAX, 0));
+    assert_eq!(
+        style
+            .measure_string("a", Point::zero(), Baseline::Top)
+            .bounding_box
+            .size,
+        Size::new(6, 12)
+    );
+
+    let mut display = MockDisplay::new();
+    display.set_allow_out_of_bounds_drawing(true);
+    let next = Text::with_baseline("AB", Point::zero(), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+    assert_eq!(next, Point::new(i32::MAX, 0));
+    assert_glyph_at(&font, &display, 'A', Point::zero(), None);
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "gzip")]
+
+use psf2::gzip::{self, GzipError};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+const FONT_GZ: &[u8] = include_bytes!("../Tamzen6x12.psf.gz");
+
+#[test]
+fn decompress() {
+    assert!(gzip::is_gzip(FONT_GZ));
+    assert!(!gzip::is_gzip(FONT));
+    assert_eq!(gzip::decompressed_len(FONT_GZ).unwrap(), FONT.len());
+    let mut buf = [0; 4096];
+    let len = gzip::decompress(FONT_GZ, &mut buf).unwrap();
+    assert_eq!(&buf[..len], FONT);
+}
+
+#[test]
+fn small_buffer() {
+    let mut buf = [0; 64];
+    assert!(matches!(
+        gzip::decompress(FONT_GZ, &mut buf),
+        Err(GzipError::BufferTooSmall)
+    ));
+}
+
+#[test]
+fn corrupt() {
+    let mut data = FONT_GZ.to_vec();
+    let i = data.len() / 2;
+    data[i] ^= 0x55;
+    let mut buf = [0; 4096];
+    assert!(matches!(
+        gzip::decompress(&data, &mut buf),
+        Err(GzipError::Corrupt)
+    ));
+    assert!(gzip::decompress(&FONT_GZ[..FONT_GZ.len() - 8], &mut buf).is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn from_gzip() {
+    use psf2::Font;
+
+    let font = Font::from_gzip(FONT_GZ).unwrap();
+    assert_eq!(font.width(), 6);
+    assert_eq!(font.height(), 12);
+    let plain = Font::from_gzip(FONT).unwrap();
+    assert_eq!(
+        font.get_unicode('A').unwrap().data(),
+        plain.get_unicode('A').unwrap().data()
+    );
+
+    // An implausible size in the trailer is rejected before allocating
+    let mut data = FONT_GZ.to_vec();
+    let len = data.len();
+    data[len - 4..].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(Font::from_gzip(&data), Err(GzipError::Corrupt)));
+}
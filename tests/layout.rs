@@ -0,0 +1,182 @@
+use psf2::{Align, Font, LayoutOptions, RenderOptions};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+
+/// The text and position of each line
+fn lines(font: &Font<&[u8]>, text: &str, options: &LayoutOptions) -> Vec<(String, u32, u32)> {
+    font.layout(text, options)
+        .map(|line| (line.text().to_string(), line.x(), line.y()))
+        .collect()
+}
+
+fn texts(font: &Font<&[u8]>, text: &str, options: &LayoutOptions) -> Vec<String> {
+    lines(font, text, options)
+        .into_iter()
+        .map(|x| x.0)
+        .collect()
+}
+
+#[test]
+fn wrapping() {
+    let font = Font::new(FONT).unwrap();
+    let options = LayoutOptions::default().with_cells(&font, 10, 10);
+    assert_eq!(
+        texts(&font, "the quick brown fox jumps", &options),
+        ["the quick", "brown fox", "jumps"]
+    );
+    // Spaces hang past the edge instead of starting a line
+    assert_eq!(
+        texts(&font, "0123456789   x", &options),
+        ["0123456789", "x"]
+    );
+    assert_eq!(
+        texts(&font, "a well-known pre-2000 words", &options),
+        ["a well-", "known", "pre-2000", "words"]
+    );
+    // No break at a no-break space, so the word is split where it overflows
+    assert_eq!(
+        texts(&font, "abcde\u{a0}fghijk", &options),
+        ["abcde\u{a0}fghi", "jk"]
+    );
+    assert_eq!(texts(&font, "ab\r\n\ncd\n", &options), ["ab", "", "cd", ""]);
+
+    let narrow = LayoutOptions::default().with_cells(&font, 1, 10);
+    assert_eq!(texts(&font, "ab", &narrow), ["a", "b"]);
+
+    let unwrapped = LayoutOptions {
+        wrap: false,
+        ..LayoutOptions::default()
+    };
+    assert_eq!(
+        texts(&font, "the quick brown", &unwrapped),
+        ["the quick brown"]
+    );
+}
+
+#[test]
+fn positions() {
+    let font = Font::new(FONT).unwrap();
+    let options = LayoutOptions {
+        render: RenderOptions {
+            letter_spacing: 1,
+            line_spacing: 2,
+            tab_width: 4,
+            ..RenderOptions::default()
+        },
+        ..LayoutOptions::default()
+    };
+    let layout = font.layout("AB\n\tC?\u{1f600}", &options);
+    assert_eq!(layout.size(), (7 * 7 - 1, 2 * 14 - 2));
+    let glyphs = layout
+        .flat_map(|line| line.glyphs().collect::<Vec<_>>())
+        .map(|x| (x.x, x.y, x.glyph.data().to_vec()))
+        .collect::<Vec<_>>();
+    let data = |c| font.get_unicode(c).unwrap().data().to_vec();
+    assert_eq!(
+        glyphs,
+        [
+            (0, 0, data('A')),
+            (7, 0, data('B')),
+            (4 * 7, 14, data('C')),
+            (5 * 7, 14, data('?')),
+            // Missing characters are replaced by the fallback
+            (6 * 7, 14, data('?')),
+        ]
+    );
+}
+
+#[test]
+fn alignment() {
+    let font = Font::new(FONT).unwrap();
+    let boxed = LayoutOptions {
+        align: Align::Center,
+        ..LayoutOptions::default().with_cells(&font, 10, 10)
+    };
+    assert_eq!(
+        lines(&font, "abcd\nabc", &boxed),
+        [("abcd".into(), 18, 0), ("abc".into(), 21, 12)]
+    );
+    let right = LayoutOptions {
+        align: Align::Right,
+        ..boxed
+    };
+    assert_eq!(
+        lines(&font, "abcd\nabc", &right),
+        [("abcd".into(), 36, 0), ("abc".into(), 42, 12)]
+    );
+    // Without a box, lines are aligned relative to the widest
+    let unboxed = LayoutOptions {
+        align: Align::Right,
+        ..LayoutOptions::default()
+    };
+    assert_eq!(
+        lines(&font, "abcd\nabc", &unboxed),
+        [("abcd".into(), 0, 0), ("abc".into(), 6, 12)]
+    );
+}
+
+#[test]
+fn truncation() {
+    let font = Font::new(FONT).unwrap();
+    let options = LayoutOptions {
+        ellipsis: Some('~'),
+        ..LayoutOptions::default().with_cells(&font, 6, 2)
+    };
+    let layout = font.layout("one two three four", &options);
+    assert_eq!(layout.size(), (24, 24));
+    let lines = layout.collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].text(), "one");
+    assert!(!lines[0].is_truncated());
+    assert_eq!(lines[1].text(), "two");
+    assert!(lines[1].is_truncated());
+    let last = lines[1].glyphs().last().unwrap();
+    assert_eq!(last.x, 18);
+    assert_eq!(last.glyph.data(), font.get_unicode('~').unwrap().data());
+
+    // Trailing spaces before the ellipsis are dropped
+    assert_eq!(texts(&font, "two   three", &options), ["two", "three"]);
+    assert_eq!(texts(&font, "abc   defghijkl", &options), ["abc", "defgh"]);
+
+    let unwrapped = LayoutOptions {
+        wrap: false,
+        ..options
+    };
+    assert_eq!(texts(&font, "abcd efgh\nxyz", &unwrapped), ["abcd", "xyz"]);
+
+    // Fonts lacking the ellipsis just cut the text short
+    let missing = LayoutOptions {
+        ellipsis: Some('…'),
+        ..unwrapped
+    };
+    let lines = font.layout("abcdefgh", &missing).collect::<Vec<_>>();
+    assert_eq!(lines[0].text(), "abcdef");
+    assert!(lines[0].is_truncated());
+    assert_eq!(lines[0].glyphs().count(), 6);
+}
+
+#[test]
+fn huge_options() {
+    let font = Font::new(FONT).unwrap();
+    let options = LayoutOptions::default().with_cells(&font, u32::MAX, u32::MAX);
+    assert_eq!(
+        (options.width, options.height),
+        (Some(u32::MAX), Some(u32::MAX))
+    );
+    assert_eq!(texts(&font, "ab cd\nef", &options), ["ab cd", "ef"]);
+
+    let options = LayoutOptions {
+        render: RenderOptions {
+            letter_spacing: u32::MAX,
+            line_spacing: u32::MAX,
+            ..RenderOptions::default()
+        },
+        ..LayoutOptions::default()
+    };
+    let layout = font.layout("ab\ncd", &options);
+    assert_eq!(layout.size(), (u32::MAX, u32::MAX));
+    let xs = layout
+        .flat_map(|line| line.glyphs().map(|x| x.x).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(xs, [0, u32::MAX, 0, u32::MAX]);
+}
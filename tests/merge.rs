@@ -0,0 +1,159 @@
+#![cfg(feature = "alloc")]
+
+use psf2::{Conflict, Font, FontBuilder, MergeError, MergeOptions, OwnedFont};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+
+/// Build an 8x1 font from glyph bitmaps and their characters
+fn font(glyphs: &[(u8, char)]) -> Font<Vec<u8>> {
+    let mut builder = FontBuilder::new(8, 1);
+    for &(bitmap, c) in glyphs {
+        builder.push_glyph(&[bitmap], &[c]).unwrap();
+    }
+    builder.build().unwrap()
+}
+
+/// Bitmap and characters of each glyph
+fn contents(font: &OwnedFont) -> Vec<(u8, Vec<char>)> {
+    (0..font.glyph_count())
+        .map(|i| (font.get_index(i).unwrap().data()[0], font.chars(i).to_vec()))
+        .collect()
+}
+
+#[test]
+fn conflicts() {
+    let fonts = [font(&[(1, 'a'), (2, 'b')]), font(&[(3, 'b'), (4, 'c')])];
+    let mut options = MergeOptions::default();
+    let merged = OwnedFont::merge(&fonts, &options).unwrap();
+    assert_eq!(
+        contents(&merged),
+        [(1, vec!['a']), (2, vec!['b']), (4, vec!['c'])]
+    );
+
+    options.conflict = Conflict::LastWins;
+    let merged = OwnedFont::merge(&fonts, &options).unwrap();
+    assert_eq!(
+        contents(&merged),
+        [(1, vec!['a']), (3, vec!['b']), (4, vec!['c'])]
+    );
+
+    options.conflict = Conflict::Error;
+    assert_eq!(
+        OwnedFont::merge(&fonts, &options).unwrap_err(),
+        MergeError::Conflict { index: 1, glyph: 0 }
+    );
+}
+
+#[test]
+fn sequences() {
+    let mut builder = FontBuilder::new(8, 1);
+    let e = builder.push_glyph(&[1], &['é']).unwrap();
+    builder.add_sequence(e, &['e', '\u{301}']);
+    let mut other = FontBuilder::new(8, 1);
+    let e = other.push_glyph(&[2], &['ė']).unwrap();
+    other.add_sequence(e, &['e', '\u{301}']);
+    let fonts = [builder.build().unwrap(), other.build().unwrap()];
+    let options = MergeOptions {
+        conflict: Conflict::LastWins,
+        ..MergeOptions::default()
+    };
+    let merged = OwnedFont::merge(&fonts, &options).unwrap().build().unwrap();
+    assert_eq!(merged.get_unicode('é').unwrap().data(), &[1]);
+    assert_eq!(merged.get_sequence(&['e', '\u{301}']).unwrap().data(), &[2]);
+}
+
+#[test]
+fn dedupe() {
+    let fonts = [font(&[(1, 'a'), (2, 'b')]), font(&[(1, 'c')])];
+    let merged = OwnedFont::merge(&fonts, &MergeOptions::default()).unwrap();
+    assert_eq!(contents(&merged), [(1, vec!['a', 'c']), (2, vec!['b'])]);
+
+    let options = MergeOptions {
+        dedupe: false,
+        ..MergeOptions::default()
+    };
+    let merged = OwnedFont::merge(&fonts, &options).unwrap();
+    assert_eq!(merged.glyph_count(), 3);
+    assert_eq!(merged.find('c'), Some(2));
+
+    let options = MergeOptions {
+        max_glyphs: Some(2),
+        ..options
+    };
+    assert_eq!(
+        OwnedFont::merge(&fonts, &options).unwrap_err(),
+        MergeError::TooManyGlyphs { count: 3 }
+    );
+}
+
+#[test]
+fn console() {
+    // Extend a font with a glyph it lacks
+    let mut builder = FontBuilder::new(6, 12);
+    let mut ellipsis = [0; 12];
+    ellipsis[8] = 0xa8;
+    builder.push_glyph(&ellipsis, &['…']).unwrap();
+    builder.push_glyph(&[0xfc; 12], &['A']).unwrap();
+    let fonts = [Font::new(FONT.to_vec()).unwrap(), builder.build().unwrap()];
+    let options = MergeOptions {
+        max_glyphs: Some(512),
+        ..MergeOptions::default()
+    };
+    let merged = OwnedFont::merge(&fonts, &options).unwrap();
+    // The 173 mapped glyphs of the first font and the ellipsis, with the second 'A' dropped
+    assert_eq!(merged.glyph_count(), 174);
+    let data = merged.to_psf1().unwrap();
+    let font = Font::new(&data[..]).unwrap();
+    assert_eq!(font.get_unicode('…').unwrap().data(), &ellipsis);
+    assert_eq!(
+        font.get_unicode('A').unwrap().data(),
+        fonts[0].get_unicode('A').unwrap().data()
+    );
+
+    let options = MergeOptions {
+        max_glyphs: Some(173),
+        ..options
+    };
+    assert_eq!(
+        OwnedFont::merge(&fonts, &options).unwrap_err(),
+        MergeError::TooManyGlyphs { count: 174 }
+    );
+}
+
+#[test]
+fn implicit() {
+    // Fonts without a table contribute glyphs by code point
+    let mut builder = FontBuilder::new(8, 1);
+    for i in 0..4 {
+        builder.push_glyph(&[0x10 + i], &[]).unwrap();
+    }
+    let fonts = [font(&[(1, '\u{1}')]), builder.build().unwrap()];
+    let merged = OwnedFont::merge(&fonts, &MergeOptions::default()).unwrap();
+    assert_eq!(
+        contents(&merged),
+        [
+            (1, vec!['\u{1}']),
+            (0x10, vec!['\u{0}']),
+            (0x12, vec!['\u{2}']),
+            (0x13, vec!['\u{3}'])
+        ]
+    );
+}
+
+#[test]
+fn errors() {
+    let options = MergeOptions::default();
+    assert_eq!(
+        OwnedFont::merge::<&[u8]>(&[], &options).unwrap_err(),
+        MergeError::Empty
+    );
+    let fonts = [Font::new(FONT.to_vec()).unwrap(), font(&[(1, 'a')])];
+    assert_eq!(
+        OwnedFont::merge(&fonts, &options).unwrap_err(),
+        MergeError::SizeMismatch {
+            index: 1,
+            width: 8,
+            height: 1
+        }
+    );
+}
@@ -0,0 +1,165 @@
+#![cfg(feature = "alloc")]
+
+use psf2::{Font, FontBuilder, OwnedFont, WriteError};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+
+#[test]
+fn edit_pixels() {
+    let font = Font::new(FONT).unwrap();
+    let mut owned = OwnedFont::from(&font);
+    assert_eq!(owned.glyph_count(), 256);
+    assert_eq!(owned.to_psf2().unwrap(), FONT);
+
+    let index = owned.find('A').unwrap();
+    let mut glyph = owned.glyph_mut(index).unwrap();
+    assert_eq!((glyph.width(), glyph.height()), (6, 12));
+    assert_eq!(glyph.pixel(0, 0), Some(false));
+    glyph.set(0, 0);
+    assert_eq!(glyph.pixel(0, 0), Some(true));
+    assert_eq!(glyph.data()[0], 0x80);
+    glyph.toggle(5, 11);
+    glyph.toggle(0, 0);
+    glyph.clear(1, 0);
+    assert_eq!(glyph.pixel(6, 0), None);
+    assert_eq!(owned.glyph_mut(256).map(|_| ()), None);
+
+    let edited = owned.build().unwrap();
+    let a = edited.get_unicode('A').unwrap();
+    let original = font.get_unicode('A').unwrap();
+    assert_eq!(a.data()[..11], original.data()[..11]);
+    assert_eq!(a.data()[11], original.data()[11] ^ 0x04);
+    assert_eq!(
+        edited.get_unicode('B').unwrap().data(),
+        font.get_unicode('B').unwrap().data()
+    );
+}
+
+#[test]
+#[should_panic]
+fn pixel_out_of_bounds() {
+    let mut owned = OwnedFont::new(6, 2);
+    owned.push_glyph(&[0; 2], &[]).unwrap();
+    owned.glyph_mut(0).unwrap().set(6, 0);
+}
+
+#[test]
+fn insert_remove() {
+    let mut owned = OwnedFont::new(8, 1);
+    owned.push_glyph(&[1], &['a']).unwrap();
+    owned.push_glyph(&[3], &['c']).unwrap();
+    owned.insert_glyph(1, &[2], &['b']).unwrap();
+    assert_eq!(
+        owned.insert_glyph(0, &[0; 2], &[]),
+        Err(WriteError::BitmapSize {
+            expected: 1,
+            actual: 2
+        })
+    );
+    assert_eq!(
+        OwnedFont::new(0, 5).push_glyph(&[], &['a']),
+        Err(WriteError::ZeroDimension {
+            width: 0,
+            height: 5
+        })
+    );
+    assert_eq!(owned.glyph_count(), 3);
+    assert_eq!(owned.find('c'), Some(2));
+    assert_eq!(owned.get_unicode('b').unwrap().data(), &[2]);
+
+    owned.remove_glyph(0);
+    assert_eq!(owned.glyph_count(), 2);
+    assert_eq!(owned.find('a'), None);
+    assert_eq!(owned.chars(1), &['c']);
+    let font = owned.build().unwrap();
+    assert_eq!(font.get_unicode('b').unwrap().data(), &[2]);
+    assert_eq!(font.get_unicode('c').unwrap().data(), &[3]);
+}
+
+#[test]
+fn mappings() {
+    let font = Font::new(FONT).unwrap();
+    let mut owned = OwnedFont::from(&font);
+    let a = owned.find('A').unwrap();
+    assert!(owned.chars(a).contains(&'Α'));
+    assert!(owned.remove_char('Α'));
+    assert!(!owned.remove_char('Α'));
+    owned.add_sequence(a, &['A', '\u{301}']);
+    owned.add_char(a, 'Ⓐ');
+    assert_eq!(owned.sequences(a), &[vec!['A', '\u{301}']]);
+
+    let edited = owned.build().unwrap();
+    assert!(edited.get_unicode('Α').is_none());
+    let glyph = font.get_unicode('A').unwrap().data();
+    assert_eq!(edited.get_unicode('Ⓐ').unwrap().data(), glyph);
+    assert_eq!(
+        edited.get_sequence(&['A', '\u{301}']).unwrap().data(),
+        glyph
+    );
+
+    assert!(owned.remove_sequence(&['A', '\u{301}']));
+    assert!(owned.sequences(a).is_empty());
+
+    // Clearing every mapping leaves an empty table
+    for i in 0..owned.glyph_count() {
+        owned.clear_mappings(i);
+    }
+    assert_eq!(owned.find('A'), None);
+    let cleared = owned.build().unwrap();
+    assert!(cleared.get_unicode('A').is_none());
+    assert_eq!(cleared.glyphs().flat_map(|(_, _, x)| x).count(), 0);
+}
+
+#[test]
+fn keeps_table() {
+    // Unmapping the only mapped character doesn't switch to indexing by code point
+    let mut builder = FontBuilder::new(8, 1);
+    for i in 0..100 {
+        builder.push_glyph(&[i], &[]).unwrap();
+    }
+    builder.add_char(0, 'x');
+    let font = builder.build().unwrap();
+    let mut owned = OwnedFont::from(&font);
+    assert!(owned.remove_char('x'));
+    assert!(owned.get_unicode('A').is_none());
+    let data = owned.to_psf1().unwrap();
+    assert_eq!(data[2], 0x02);
+
+    // Nor does a font read with a table of empty entries
+    let font = Font::new(&owned.to_psf2().unwrap()[..]).unwrap().to_vec();
+    assert!(OwnedFont::from(&font).get_unicode('A').is_none());
+}
+
+#[test]
+fn starts_table() {
+    // Mapping a new glyph in a font indexed by code point keeps the old glyphs reachable
+    let mut builder = FontBuilder::new(8, 1);
+    for i in 0..128 {
+        builder.push_glyph(&[i], &[]).unwrap();
+    }
+    let font = builder.build().unwrap();
+    let mut owned = OwnedFont::from(&font);
+    let logo = owned.push_glyph(&[0xff], &['\u{e000}']).unwrap();
+    assert_eq!(logo, 128);
+    assert_eq!(owned.get_unicode('A').unwrap().data(), b"A");
+    assert_eq!(owned.chars(65), &['A']);
+    let built = owned.build().unwrap();
+    assert_eq!(built.get_unicode('A').unwrap().data(), b"A");
+    assert_eq!(built.get_unicode('\u{e000}').unwrap().data(), &[0xff]);
+
+    let mut owned = OwnedFont::from(&font);
+    owned.add_char(0, '\u{e000}');
+    assert_eq!(owned.chars(0), &['\0', '\u{e000}']);
+    assert_eq!(owned.find('z'), Some(122));
+}
+
+#[test]
+fn from_builder() {
+    let mut builder = FontBuilder::new(4, 1);
+    builder.push_glyph(&[0x10], &['x']).unwrap();
+    let mut owned = builder.into_owned();
+    owned.glyph_mut(0).unwrap().set(0, 0);
+    let data = owned.to_psf1().unwrap();
+    let font = Font::new(&data[..]).unwrap();
+    assert_eq!(font.get_unicode('x').unwrap().data(), &[0x90]);
+}
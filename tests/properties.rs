@@ -0,0 +1,208 @@
+//! Consistency checks for glyph iteration over randomly generated fonts
+
+mod common;
+
+use psf2::Font;
+
+/// Minimal xorshift PRNG, so that failures are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn range(&mut self, lo: u32, hi: u32) -> u32 {
+        lo + (self.next() % u64::from(hi - lo + 1)) as u32
+    }
+}
+
+/// A PSF2 font of `length` random glyphs
+fn random_font(rng: &mut Rng, width: u32, height: u32, length: u32) -> Vec<u8> {
+    let charsize = width.div_ceil(8) * height;
+    let bitmaps = (0..charsize * length)
+        .map(|_| rng.next() as u8)
+        .collect::<Vec<_>>();
+    common::psf2(width, height, &bitmaps, None)
+}
+
+/// Pixels of a glyph computed directly from its bitmap
+fn reference(data: &[u8], width: u32, height: u32) -> Vec<Vec<bool>> {
+    let stride = width.div_ceil(8) as usize;
+    (0..height as usize)
+        .map(|y| {
+            (0..width as usize)
+                .map(|x| data[y * stride + x / 8] & (0x80 >> (x % 8)) != 0)
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn glyph_iteration() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..500 {
+        let width = rng.range(1, 40);
+        let height = rng.range(1, 24);
+        let data = random_font(&mut rng, width, height, 2);
+        let font = Font::new(&data[..]).unwrap();
+        for index in 0..2 {
+            let glyph = font.get_index(index).unwrap();
+            let expected = reference(glyph.data(), width, height);
+
+            assert_eq!(glyph.len(), height as usize);
+            assert_eq!((glyph.width(), glyph.height()), (width, height));
+            for y in 0..height + 1 {
+                for x in 0..width + 1 {
+                    let expected = expected.get(y as usize).and_then(|row| row.get(x as usize));
+                    assert_eq!(glyph.pixel(x, y), expected.copied());
+                }
+                assert_eq!(
+                    glyph.row(y).map(|row| row.collect::<Vec<_>>()).as_ref(),
+                    expected.get(y as usize)
+                );
+            }
+
+            let masks = glyph
+                .clone()
+                .map(|row| row.as_u128().unwrap())
+                .collect::<Vec<_>>();
+            let expected_masks = expected
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .enumerate()
+                        .fold(0, |acc, (x, &p)| acc | (u128::from(p) << (127 - x)))
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(masks, expected_masks);
+            match glyph.rows_u32() {
+                Some(rows) => assert!(rows.eq(masks.iter().map(|&x| (x >> 96) as u32))),
+                None => assert!(width > 32),
+            }
+            assert_eq!(glyph.size_hint(), (height as usize, Some(height as usize)));
+
+            let forward = glyph
+                .clone()
+                .map(|row| {
+                    assert_eq!(row.len(), width as usize);
+                    row.collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(forward, expected);
+
+            let mut backward = glyph
+                .clone()
+                .rev()
+                .map(|row| {
+                    let mut row = row.rev().collect::<Vec<_>>();
+                    row.reverse();
+                    row
+                })
+                .collect::<Vec<_>>();
+            backward.reverse();
+            assert_eq!(backward, expected);
+
+            assert!(glyph
+                .clone()
+                .rev()
+                .enumerate()
+                .map(|(i, _)| i)
+                .eq(0..height as usize));
+        }
+    }
+}
+
+#[test]
+fn mixed_direction() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..500 {
+        let width = rng.range(1, 40);
+        let height = rng.range(1, 24);
+        let data = random_font(&mut rng, width, height, 1);
+        let font = Font::new(&data[..]).unwrap();
+        let mut glyph = font.get_index(0).unwrap();
+        let expected = reference(glyph.data(), width, height);
+
+        // Consume rows from both ends at random, checking the remaining length as we go
+        let (mut front, mut back) = (0, height as usize);
+        while front < back {
+            assert_eq!(glyph.len(), back - front);
+            let (row, y) = if rng.next() & 1 == 0 {
+                front += 1;
+                (glyph.next().unwrap(), front - 1)
+            } else {
+                back -= 1;
+                (glyph.next_back().unwrap(), back)
+            };
+
+            let (mut left, mut right) = (0, width as usize);
+            let mut row = row;
+            while left < right {
+                assert_eq!(row.len(), right - left);
+                if rng.next() & 1 == 0 {
+                    assert_eq!(row.next(), Some(expected[y][left]));
+                    left += 1;
+                } else {
+                    right -= 1;
+                    assert_eq!(row.next_back(), Some(expected[y][right]));
+                }
+            }
+            assert_eq!(row.len(), 0);
+            assert_eq!(row.next(), None);
+            assert_eq!(row.next_back(), None);
+        }
+        assert_eq!(glyph.len(), 0);
+        assert!(glyph.next().is_none());
+        assert!(glyph.next_back().is_none());
+    }
+}
+
+#[test]
+fn blit() {
+    let mut rng = Rng(0x853c_49e6_748f_ea9b);
+    for _ in 0..500 {
+        let width = rng.range(1, 40);
+        let height = rng.range(1, 24);
+        let data = random_font(&mut rng, width, height, 1);
+        let font = Font::new(&data[..]).unwrap();
+        let glyph = font.get_index(0).unwrap();
+        let expected = reference(glyph.data(), width, height);
+
+        let stride = (width + rng.range(0, 3)) as usize;
+        let mut opaque = vec![0u32; stride * height as usize];
+        glyph.blit(&mut opaque, stride, 1, Some(2));
+        let mut transparent = vec![3u32; stride * height as usize];
+        glyph.blit(&mut transparent, stride, 1, None);
+        let mut bytes = vec![0u8; stride * 3 * height as usize];
+        glyph.blit_bytes(&mut bytes, stride * 3, [1, 1, 1], Some([2, 2, 2]));
+
+        for (y, row) in expected.iter().enumerate() {
+            for x in 0..stride {
+                let i = y * stride + x;
+                let pixel = row.get(x).copied();
+                let (opaque_pixel, transparent_pixel) = match pixel {
+                    Some(true) => (1, 1),
+                    Some(false) => (2, 3),
+                    None => (0, 3),
+                };
+                assert_eq!(opaque[i], opaque_pixel);
+                assert_eq!(transparent[i], transparent_pixel);
+                assert_eq!(bytes[i * 3..i * 3 + 3], [opaque_pixel as u8; 3]);
+            }
+        }
+
+        // Clipping to a small target
+        let mut small = vec![0u32; width as usize + 1];
+        glyph.blit(&mut small, width as usize, 1, Some(2));
+        for (x, &p) in small[..width as usize].iter().enumerate() {
+            assert_eq!(p, if expected[0][x] { 1 } else { 2 });
+        }
+        if height > 1 {
+            assert_eq!(small[width as usize], if expected[1][0] { 1 } else { 2 });
+        }
+    }
+}
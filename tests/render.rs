@@ -0,0 +1,105 @@
+use psf2::{Canvas, Font, Format, RenderOptions};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+
+/// Render `text` into a fresh 8-bpp image of the given size
+fn render(font: &Font<&[u8]>, text: &str, options: &RenderOptions, size: (u32, u32)) -> Vec<u8> {
+    let mut buf = vec![0; Format::Gray8.buffer_len(size.0, size.1)];
+    let mut canvas = Canvas::new(&mut buf, Format::Gray8, size.0, size.1).unwrap();
+    font.render_str(text, &mut canvas, options);
+    buf
+}
+
+/// Check that the `w` by `h` image `image` contains `c` at (`x`, `y`)
+fn assert_glyph_at(font: &Font<&[u8]>, image: &[u8], w: u32, c: char, x: u32, y: u32) {
+    let glyph = font.get_unicode(c).unwrap();
+    for (dy, row) in glyph.enumerate() {
+        for (dx, filled) in row.enumerate() {
+            let i = (y as usize + dy) * w as usize + x as usize + dx;
+            assert_eq!(image[i] != 0, filled, "{c:?} at ({dx}, {dy})");
+        }
+    }
+}
+
+#[test]
+fn layout() {
+    let font = Font::new(FONT).unwrap();
+    let options = RenderOptions {
+        letter_spacing: 1,
+        line_spacing: 2,
+        tab_width: 4,
+        ..RenderOptions::default()
+    };
+    let text = "AB\n\tC\r\nD";
+    let (w, h) = font.measure_str(text, &options);
+    assert_eq!((w, h), (5 * 7 - 1, 3 * 14 - 2));
+    let image = render(&font, text, &options, (w, h));
+    assert_glyph_at(&font, &image, w, 'A', 0, 0);
+    assert_glyph_at(&font, &image, w, 'B', 7, 0);
+    assert_glyph_at(&font, &image, w, 'C', 4 * 7, 14);
+    assert_glyph_at(&font, &image, w, 'D', 0, 28);
+    // Spacing is left blank
+    assert!((0..h as usize).all(|y| image[y * w as usize + 6] == 0));
+}
+
+#[test]
+fn fallback() {
+    let font = Font::new(FONT).unwrap();
+    let options = RenderOptions::default();
+    assert_eq!(font.measure_str("a\u{1F600}b", &options), (18, 12));
+    let image = render(&font, "a\u{1F600}b", &options, (18, 12));
+    assert_glyph_at(&font, &image, 18, '?', 6, 0);
+
+    let options = RenderOptions {
+        fallback: None,
+        ..options
+    };
+    let image = render(&font, "a\u{1F600}b", &options, (18, 12));
+    assert_glyph_at(&font, &image, 18, 'b', 12, 0);
+    assert!((0..12).all(|y| image[y * 18 + 6..y * 18 + 12].iter().all(|&x| x == 0)));
+}
+
+#[test]
+fn mono() {
+    let font = Font::new(FONT).unwrap();
+    let options = RenderOptions::default();
+    let (w, h) = font.measure_str("AB", &options);
+    assert_eq!(Format::Mono.stride(w), 2);
+    let mut buf = vec![0; Format::Mono.buffer_len(w, h)];
+    let mut canvas = Canvas::new(&mut buf, Format::Mono, w, h).unwrap();
+    font.render_str("AB", &mut canvas, &options);
+    let gray = render(&font, "AB", &options, (w, h));
+    for y in 0..h as usize {
+        for x in 0..w as usize {
+            let bit = buf[y * 2 + x / 8] & (0x80 >> (x % 8)) != 0;
+            assert_eq!(bit, gray[y * w as usize + x] != 0);
+        }
+    }
+}
+
+#[test]
+fn clipping() {
+    let font = Font::new(FONT).unwrap();
+    let options = RenderOptions::default();
+    assert!(Canvas::new(&mut [0; 10], Format::Gray8, 4, 4).is_none());
+    let full = render(&font, "AB", &options, (12, 12));
+    let clipped = render(&font, "AB", &options, (8, 5));
+    for y in 0..5 {
+        assert_eq!(clipped[y * 8..y * 8 + 8], full[y * 12..y * 12 + 8]);
+    }
+}
+
+#[test]
+fn huge_spacing() {
+    let font = Font::new(FONT).unwrap();
+    let options = RenderOptions {
+        letter_spacing: u32::MAX - 2,
+        line_spacing: u32::MAX,
+        ..RenderOptions::default()
+    };
+    assert_eq!(font.measure_str("A", &options), (6, 12));
+    let (w, h) = font.measure_str("A\tB\nC", &options);
+    assert_eq!((w, h), (u32::MAX, u32::MAX));
+    let image = render(&font, "AB\nC", &options, (16, 16));
+    assert_glyph_at(&font, &image, 16, 'A', 0, 0);
+}
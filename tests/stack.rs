@@ -0,0 +1,81 @@
+mod common;
+
+use psf2::{Font, FontStack, StackError};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+
+#[test]
+fn resolution() {
+    // Covers 'A' as well, but comes second
+    let extra = common::psf2(
+        6,
+        12,
+        &[[0xaa; 12], [0xfc; 12]].concat(),
+        Some(&["ЖA".as_bytes(), "\u{2500}".as_bytes()]),
+    );
+    let fonts = [Font::new(FONT).unwrap(), Font::new(&extra[..]).unwrap()];
+    let stack = FontStack::new(&fonts).unwrap();
+    assert_eq!((stack.width(), stack.height()), (6, 12));
+
+    let glyph = stack.get('A').unwrap();
+    assert_eq!((glyph.x, glyph.y), (0, 0));
+    assert_eq!(
+        glyph.glyph.data(),
+        fonts[0].get_unicode('A').unwrap().data()
+    );
+    assert_eq!(stack.get('Ж').unwrap().glyph.data(), &[0xaa; 12]);
+    assert!(stack.get('\u{1f600}').is_none());
+
+    let glyphs = stack
+        .glyphs_for_str("A\u{2500}\u{1f600}Ж")
+        .map(|x| x.map(|x| x.glyph.data()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        glyphs,
+        [
+            Ok(fonts[0].get_unicode('A').unwrap().data()),
+            Ok(&[0xfc; 12][..]),
+            Err('\u{1f600}'),
+            Ok(&[0xaa; 12][..]),
+        ]
+    );
+}
+
+#[test]
+fn sizes() {
+    let small = common::psf2(4, 8, &[0xf0; 8], Some(&["Ж".as_bytes()]));
+    let fonts = [Font::new(FONT).unwrap(), Font::new(&small[..]).unwrap()];
+    assert_eq!(
+        FontStack::new(&fonts).err().unwrap(),
+        StackError::SizeMismatch {
+            index: 1,
+            width: 4,
+            height: 8
+        }
+    );
+    assert_eq!(
+        FontStack::<&[u8]>::new(&[]).err().unwrap(),
+        StackError::Empty
+    );
+
+    let stack = FontStack::centered(&fonts).unwrap();
+    assert_eq!((stack.width(), stack.height()), (6, 12));
+    let glyph = stack.get('Ж').unwrap();
+    assert_eq!((glyph.x, glyph.y), (1, 2));
+    assert_eq!(glyph.glyph.data(), &[0xf0; 8]);
+    let glyph = stack.get('A').unwrap();
+    assert_eq!((glyph.x, glyph.y), (0, 0));
+
+    // The cell is as large as the largest font in each dimension
+    let wide = common::psf2(9, 4, &[0xff, 0x80].repeat(4), Some(&[b"x"]));
+    let fonts = [
+        Font::new(&small[..]).unwrap(),
+        Font::new(&wide[..]).unwrap(),
+    ];
+    let stack = FontStack::centered(&fonts).unwrap();
+    assert_eq!((stack.width(), stack.height()), (9, 8));
+    let glyph = stack.get('x').unwrap();
+    assert_eq!((glyph.x, glyph.y), (0, 2));
+    let glyph = stack.get('Ж').unwrap();
+    assert_eq!((glyph.x, glyph.y), (2, 0));
+}
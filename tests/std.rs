@@ -0,0 +1,60 @@
+#![cfg(feature = "std")]
+
+use std::{error::Error, io};
+
+use psf2::{Font, OpenError, ParseError};
+
+#[test]
+fn open() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/Tamzen6x12.psf");
+    let font = Font::open(path).unwrap();
+    assert_eq!(font.width(), 6);
+    assert_eq!(font.data(), std::fs::read(path).unwrap());
+
+    let err = Font::open(concat!(env!("CARGO_MANIFEST_DIR"), "/missing.psf"))
+        .err()
+        .unwrap();
+    assert!(matches!(err, OpenError::Io(_)));
+
+    let err = Font::open(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .err()
+        .unwrap();
+    assert!(matches!(err, OpenError::Parse(ParseError::BadMagic)));
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ParseError::BadMagic.to_string()
+    );
+}
+
+#[test]
+fn owned() {
+    let data = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/Tamzen6x12.psf")).unwrap();
+    let owned = Font::new(&data[..]).unwrap().to_vec();
+    drop(data);
+    assert_eq!(owned.glyph_count(), 256);
+    assert!(owned.get_unicode('A').is_some());
+    assert_eq!(owned.into_inner().len(), 3969);
+}
+
+#[test]
+fn io_error() {
+    fn load(data: &[u8]) -> io::Result<Font<&[u8]>> {
+        Ok(Font::new(data)?)
+    }
+    let err = load(b"not a font, but long enough to have a header")
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn unicode_index() {
+    use psf2::UnicodeIndex;
+
+    let font = Font::open(concat!(env!("CARGO_MANIFEST_DIR"), "/Tamzen6x12.psf")).unwrap();
+    let index = UnicodeIndex::new(&font).unwrap();
+    assert_eq!(index.get('A'), Some(65));
+    assert_eq!(index.get('\u{410}'), Some(65));
+    assert_eq!(index.get('¤'), Some(0));
+    assert_eq!(index.get('\u{1F600}'), None);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "alloc")]
+
+use psf2::{Font, FontBuilder, SubsetOptions};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+
+#[test]
+fn dense() {
+    let font = Font::new(FONT).unwrap();
+    let subset = font.subset("Hello, Ω!", &SubsetOptions::default());
+    // Duplicates and characters the font lacks are dropped
+    assert_eq!(subset.glyph_count(), 7);
+    let built = subset.build().unwrap();
+    for c in "Hello, !".chars() {
+        assert_eq!(
+            built.get_unicode(c).unwrap().data(),
+            font.get_unicode(c).unwrap().data(),
+            "{c:?}"
+        );
+    }
+    // Other mappings of kept glyphs survive
+    assert!(built.get_unicode('Ω').is_none());
+    assert!(built.get_unicode('Η').is_some());
+    assert!(built.get_unicode('A').is_none());
+    // Relative order is retained
+    assert!(subset.find(' ').unwrap() < subset.find('H').unwrap());
+    assert_eq!(subset.find(' '), Some(0));
+}
+
+#[test]
+fn preserve_ascii() {
+    let font = Font::new(FONT).unwrap();
+    let options = SubsetOptions {
+        preserve_ascii: true,
+    };
+    let subset = font.subset("BA¤", &options);
+    assert_eq!(subset.glyph_count(), 67);
+    assert_eq!(subset.find('A'), Some(65));
+    assert_eq!(subset.find('B'), Some(66));
+    // Other glyphs fill the gaps
+    assert_eq!(subset.find('¤'), Some(0));
+    assert!(subset.get_index(1).unwrap().data().iter().all(|&x| x == 0));
+    assert!(subset.chars(1).is_empty());
+    let built = subset.build().unwrap();
+    assert_eq!(
+        built.get_unicode('¤').unwrap().data(),
+        font.get_unicode('¤').unwrap().data()
+    );
+}
+
+#[test]
+fn sequences() {
+    let mut builder = FontBuilder::new(8, 1);
+    builder.push_glyph(&[1], &['e']).unwrap();
+    let acute = builder.push_glyph(&[2], &['é']).unwrap();
+    builder.add_sequence(acute, &['e', '\u{301}']);
+    builder.push_glyph(&[3], &['x']).unwrap();
+    let font = builder.build().unwrap();
+
+    let subset = font.subset("xe\u{301}", &SubsetOptions::default());
+    assert_eq!(subset.glyph_count(), 2);
+    let built = subset.build().unwrap();
+    assert!(built.get_unicode('e').is_none());
+    assert_eq!(built.get_unicode('é').unwrap().data(), &[2]);
+    assert_eq!(built.get_sequence(&['e', '\u{301}']).unwrap().data(), &[2]);
+    assert_eq!(built.get_unicode('x').unwrap().data(), &[3]);
+}
+
+#[test]
+fn implicit() {
+    // Fonts indexed by code point gain a table
+    let mut builder = FontBuilder::new(8, 1);
+    for i in 0..128u8 {
+        builder.push_glyph(&[i], &[]).unwrap();
+    }
+    let font = builder.build().unwrap();
+    let subset = font.subset("zä", &SubsetOptions::default());
+    assert_eq!(subset.glyph_count(), 1);
+    assert_eq!(subset.chars(0), &['z']);
+    assert_eq!(subset.get_unicode('z').unwrap().data(), b"z");
+}
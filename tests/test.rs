@@ -1,4 +1,6 @@
-use psf2::Font;
+mod common;
+
+use psf2::{Font, IndexError, Mapping, ParseError, UnicodeIndex};
 
 const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
 
@@ -7,4 +9,322 @@ fn smoke() {
     let font = Font::new(FONT).unwrap();
     assert_eq!(font.width(), 6);
     assert_eq!(font.height(), 12);
+    assert_eq!(font.glyph_count(), 256);
+}
+
+#[test]
+fn index_bounds() {
+    let font = Font::new(FONT).unwrap();
+    assert_eq!(
+        font.get_index(255).unwrap().data(),
+        &FONT[32 + 255 * 12..32 + 256 * 12]
+    );
+    // Would otherwise read from the Unicode table
+    assert!(font.get_index(256).is_none());
+    assert!(font.get_index(u32::MAX).is_none());
+
+    let data = psf1(0x01, &[]);
+    let font = Font::new(&data[..]).unwrap();
+    assert_eq!(font.glyph_count(), 512);
+    assert_eq!(font.get_index(300).unwrap().data(), &[44]);
+    assert!(font.get_index(512).is_none());
+}
+
+#[test]
+fn unicode() {
+    let font = Font::new(FONT).unwrap();
+    assert_eq!(
+        font.get_unicode('A').unwrap().data(),
+        font.get_ascii(b'A').unwrap().data()
+    );
+    // Cyrillic capital A shares a glyph with the Latin one
+    assert_eq!(
+        font.get_unicode('\u{410}').unwrap().data(),
+        font.get_ascii(b'A').unwrap().data()
+    );
+    // The first glyph is the currency sign, not NUL
+    assert_eq!(
+        font.get_unicode('¤').unwrap().data(),
+        font.get_ascii(0).unwrap().data()
+    );
+    assert!(font.get_unicode('\u{1F600}').is_none());
+}
+
+#[test]
+fn truncated_unicode_table() {
+    assert!(matches!(
+        Font::new(&FONT[..FONT.len() - 1]),
+        Err(ParseError::UnexpectedEnd { .. })
+    ));
+}
+
+#[test]
+fn sequences() {
+    let data = common::psf2(
+        8,
+        1,
+        &[0, 1, 2],
+        Some(&[
+            "e".as_bytes(),
+            "\u{301}".as_bytes(),
+            b"\xc3\xa9\xfee\xcc\x81",
+        ]),
+    );
+    let font = Font::new(&data[..]).unwrap();
+    assert_eq!(font.get_sequence(&['e', '\u{301}']).unwrap().data(), &[2]);
+    assert_eq!(font.get_sequence(&['e']).unwrap().data(), &[0]);
+    assert!(font.get_sequence(&['\u{301}', 'e']).is_none());
+    assert!(font.get_sequence(&[]).is_none());
+
+    let glyphs = font
+        .glyphs_for_str("e\u{301}ex\u{301}")
+        .map(|x| x.map(|glyph| glyph.data()[0]))
+        .collect::<Vec<_>>();
+    assert_eq!(glyphs, &[Ok(2), Ok(0), Err('x'), Ok(1)]);
+}
+
+/// Build an 8x1 PSF1 font whose glyph `i` is the byte `i`, with a Unicode table if `mode` asks
+/// for one mapping each glyph to the code point of the same index, except where overridden
+fn psf1(mode: u8, overrides: &[(usize, &[u16])]) -> Vec<u8> {
+    let length = if mode & 0x01 != 0 { 512 } else { 256 };
+    let mut data = vec![0x36, 0x04, mode, 1];
+    data.extend((0..length).map(|i| i as u8));
+    if mode & 0x06 != 0 {
+        for i in 0..length {
+            let entry = match overrides.iter().find(|x| x.0 == i) {
+                Some(&(_, entry)) => entry,
+                None => &[i as u16],
+            };
+            for x in entry.iter().chain(&[0xffff]) {
+                data.extend_from_slice(&x.to_le_bytes());
+            }
+        }
+    }
+    data
+}
+
+#[test]
+fn psf1_plain() {
+    let data = psf1(0, &[]);
+    let font = Font::new(&data[..]).unwrap();
+    assert_eq!(font.width(), 8);
+    assert_eq!(font.height(), 1);
+    assert_eq!(font.get_ascii(b'A').unwrap().data(), b"A");
+    assert_eq!(font.get_unicode('A').unwrap().data(), b"A");
+    assert!(font.get_unicode('\u{100}').is_none());
+    assert!(matches!(
+        Font::new(&data[..data.len() - 1]),
+        Err(ParseError::UnexpectedEnd { .. })
+    ));
+}
+
+#[test]
+fn psf1_unicode() {
+    let data = psf1(
+        0x01 | 0x02 | 0x04,
+        &[(1, &[0x263a]), (2, &[0xe9, 0xfffe, 0x65, 0x301])],
+    );
+    let font = Font::new(&data[..]).unwrap();
+    assert_eq!(font.get_unicode('☺').unwrap().data(), &[1]);
+    assert_eq!(font.get_unicode('é').unwrap().data(), &[2]);
+    assert_eq!(font.get_sequence(&['e', '\u{301}']).unwrap().data(), &[2]);
+    assert_eq!(font.get_unicode('\u{12c}').unwrap().data(), &[44]);
+    assert!(font.get_unicode('\u{200}').is_none());
+
+    let data = psf1(0x02, &[(1, &[0x41, 0xd800])]);
+    assert_eq!(
+        Font::new(&data[..]).err(),
+        Some(ParseError::BadUnicodeTable {
+            offset: 4 + 256 + 4 + 2
+        })
+    );
+}
+
+/// Overwrite the `i`th 32-bit field of a PSF2 header
+fn set_field(data: &mut [u8], i: usize, value: u32) {
+    data[i * 4..(i + 1) * 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[test]
+fn header_validation() {
+    let data = common::psf2(8, 1, &[0], Some(&[b"a"]));
+    assert!(Font::new(&data[..]).is_ok());
+
+    let mut bad = data.clone();
+    set_field(&mut bad, 1, 1);
+    assert_eq!(
+        Font::new(&bad[..]).err(),
+        Some(ParseError::UnsupportedVersion { version: 1 })
+    );
+
+    let mut bad = data.clone();
+    set_field(&mut bad, 2, 16);
+    assert_eq!(
+        Font::new(&bad[..]).err(),
+        Some(ParseError::BadHeaderSize { headersize: 16 })
+    );
+
+    let mut bad = data.clone();
+    set_field(&mut bad, 5, 2);
+    assert_eq!(
+        Font::new(&bad[..]).err(),
+        Some(ParseError::CharsizeMismatch {
+            charsize: 2,
+            expected: 1
+        })
+    );
+
+    let mut bad = data.clone();
+    set_field(&mut bad, 7, 0);
+    assert_eq!(
+        Font::new(&bad[..]).err(),
+        Some(ParseError::ZeroDimension {
+            width: 0,
+            height: 1
+        })
+    );
+
+    let mut bad = psf1(0, &[]);
+    bad[3] = 0;
+    assert_eq!(
+        Font::new(&bad[..]).err(),
+        Some(ParseError::ZeroDimension {
+            width: 8,
+            height: 0
+        })
+    );
+
+    let bad = common::psf2(8, 1, &[0, 0], Some(&[b"a", b"b\xfec\xc3"]));
+    assert_eq!(
+        Font::new(&bad[..]).err(),
+        Some(ParseError::BadUnicodeTable {
+            offset: 32 + 2 + 2 + 3
+        })
+    );
+}
+
+#[test]
+fn error_context() {
+    let err = Font::new(&FONT[..100]).err().unwrap();
+    assert_eq!(
+        err,
+        ParseError::UnexpectedEnd {
+            offset: 32,
+            expected: 256 * 12,
+            actual: 68
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "unexpected end of data at byte 32: expected at least 3072 bytes, found 68"
+    );
+
+    let err = Font::new(&FONT[..20]).err().unwrap();
+    assert_eq!(
+        err,
+        ParseError::UnexpectedEnd {
+            offset: 0,
+            expected: 32,
+            actual: 20
+        }
+    );
+
+    // The final entry of the Unicode table is missing its terminator
+    let truncated = &FONT[..FONT.len() - 1];
+    let Err(ParseError::UnexpectedEnd {
+        offset,
+        expected,
+        actual,
+    }) = Font::new(truncated)
+    else {
+        panic!("expected UnexpectedEnd");
+    };
+    assert_eq!(offset + actual, truncated.len());
+    assert_eq!(expected, actual + 1);
+}
+
+/// Flatten mappings into strings, with sequences in brackets
+fn mapping_strings<'a>(mappings: impl Iterator<Item = Mapping<'a>>) -> Vec<String> {
+    mappings
+        .map(|x| match x {
+            Mapping::Char(c) => c.to_string(),
+            Mapping::Sequence(seq) => format!("[{}]", seq.collect::<String>()),
+        })
+        .collect()
+}
+
+#[test]
+fn glyphs() {
+    let font = Font::new(FONT).unwrap();
+    let mut glyphs = font.glyphs();
+    assert_eq!(glyphs.len(), 256);
+    let (index, glyph, mappings) = glyphs.nth(65).unwrap();
+    assert_eq!(index, 65);
+    assert_eq!(glyph.data(), font.get_ascii(b'A').unwrap().data());
+    assert_eq!(mapping_strings(mappings), &["A", "А", "Α", "Ⓐ"]);
+    assert_eq!(glyphs.len(), 256 - 66);
+    assert_eq!(glyphs.count(), 256 - 66);
+
+    let data = common::psf2(
+        8,
+        1,
+        &[0, 1],
+        Some(&[b"e", b"\xc3\xa9\xfee\xcc\x81\xfe\xc3\xa9"]),
+    );
+    let font = Font::new(&data[..]).unwrap();
+    let mappings = font
+        .glyphs()
+        .map(|(_, _, x)| mapping_strings(x))
+        .collect::<Vec<_>>();
+    assert_eq!(mappings, [vec!["e"], vec!["é", "[e\u{301}]", "[é]"]]);
+
+    let data = psf1(0, &[]);
+    let font = Font::new(&data[..]).unwrap();
+    let (_, glyph, mappings) = font.glyphs().last().unwrap();
+    assert_eq!(glyph.data(), &[255]);
+    assert_eq!(mapping_strings(mappings), &["ÿ"]);
+}
+
+#[test]
+fn unicode_index() {
+    let mut bmp = vec![0; UnicodeIndex::BMP_LEN].into_boxed_slice();
+    let bmp = <&mut [u16; UnicodeIndex::BMP_LEN]>::try_from(&mut bmp[..]).unwrap();
+
+    let font = Font::new(FONT).unwrap();
+    assert_eq!(UnicodeIndex::astral_capacity(&font), 0);
+    let index = UnicodeIndex::new_in(&font, bmp, &mut []).unwrap();
+    for c in (0..0x3000).filter_map(char::from_u32) {
+        assert_eq!(
+            index.get(c).map(|i| font.get_index(i).unwrap().data()),
+            font.get_unicode(c).map(|x| x.data()),
+            "{c:?}"
+        );
+    }
+
+    let data = common::psf2(
+        8,
+        1,
+        &[0, 1, 2],
+        Some(&[
+            "\u{1F600}a".as_bytes(),
+            "\u{1F600}b".as_bytes(),
+            "\u{10000}".as_bytes(),
+        ]),
+    );
+    let font = Font::new(&data[..]).unwrap();
+    assert_eq!(UnicodeIndex::astral_capacity(&font), 3);
+    let mut astral = [('\0', 0); 3];
+    assert_eq!(
+        UnicodeIndex::new_in(&font, bmp, &mut astral[..2]).err(),
+        Some(IndexError::AstralCapacity {
+            needed: 3,
+            actual: 2
+        })
+    );
+    let index = UnicodeIndex::new_in(&font, bmp, &mut astral).unwrap();
+    assert_eq!(index.get('\u{1F600}'), Some(0));
+    assert_eq!(index.get('\u{10000}'), Some(2));
+    assert_eq!(index.get('b'), Some(1));
+    assert_eq!(index.get('\u{1F601}'), None);
+    assert_eq!(index.get('c'), None);
 }
@@ -0,0 +1,190 @@
+#![cfg(feature = "alloc")]
+
+use psf2::{Font, FontBuilder, Mapping, WriteError};
+
+const FONT: &[u8] = include_bytes!("../Tamzen6x12.psf");
+
+/// Copy every glyph and mapping of `font` into a new builder
+fn builder(font: &Font<&[u8]>) -> FontBuilder {
+    let mut builder = FontBuilder::new(font.width(), font.height());
+    for (index, glyph, mappings) in font.glyphs() {
+        assert_eq!(builder.push_glyph(glyph.data(), &[]), Ok(index));
+        for mapping in mappings {
+            match mapping {
+                Mapping::Char(c) => builder.add_char(index, c),
+                Mapping::Sequence(seq) => builder.add_sequence(index, &seq.collect::<Vec<_>>()),
+            }
+        }
+    }
+    builder
+}
+
+#[test]
+fn round_trip() {
+    let font = Font::new(FONT).unwrap();
+    let builder = builder(&font);
+    assert_eq!(builder.glyph_count(), 256);
+    assert_eq!(builder.glyph_size(), 12);
+    assert_eq!(builder.to_psf2().unwrap(), FONT);
+
+    let rebuilt = builder.build().unwrap();
+    assert_eq!(rebuilt.glyph_count(), font.glyph_count());
+    for c in ['A', 'Α', '¤', '?'] {
+        assert_eq!(
+            rebuilt.get_unicode(c).unwrap().data(),
+            font.get_unicode(c).unwrap().data()
+        );
+    }
+}
+
+#[test]
+fn sequences() {
+    let mut builder = FontBuilder::new(8, 1);
+    let e = builder.push_glyph(&[0x01], &['e']).unwrap();
+    let acute = builder.push_glyph(&[0x02], &['é']).unwrap();
+    builder.add_sequence(acute, &['e', '\u{301}']);
+    builder.add_sequence(e, &['E']);
+    builder.add_sequence(e, &[]);
+    let data = builder.to_psf2().unwrap();
+    assert_eq!(&data[32..34], &[0x01, 0x02]);
+    assert_eq!(&data[34..], b"eE\xff\xc3\xa9\xfee\xcc\x81\xff");
+
+    let font = Font::new(&data[..]).unwrap();
+    assert_eq!(font.get_unicode('E').unwrap().data(), &[0x01]);
+    assert_eq!(
+        font.get_sequence(&['e', '\u{301}']).unwrap().data(),
+        &[0x02]
+    );
+    let glyphs = font
+        .glyphs_for_str("ee\u{301}")
+        .map(|x| x.unwrap().data())
+        .collect::<Vec<_>>();
+    assert_eq!(glyphs, [&[0x01], &[0x02]]);
+}
+
+#[test]
+fn implicit() {
+    // Without any mappings, no table is written and glyphs are indexed by code point
+    let mut builder = FontBuilder::new(10, 2);
+    for i in 0..66u8 {
+        builder.push_glyph(&[i, 0, 0, i], &[]).unwrap();
+    }
+    let font = builder.build().unwrap();
+    assert_eq!(font.data().len(), 32 + 66 * 4);
+    assert_eq!((font.width(), font.height()), (10, 2));
+    assert_eq!(font.get_unicode('A').unwrap().data(), &[65, 0, 0, 65]);
+}
+
+#[test]
+fn errors() {
+    let mut builder = FontBuilder::new(9, 2);
+    assert_eq!(
+        builder.push_glyph(&[0; 2], &['x']),
+        Err(WriteError::BitmapSize {
+            expected: 4,
+            actual: 2
+        })
+    );
+    assert_eq!(builder.glyph_count(), 0);
+    assert_eq!(
+        FontBuilder::new(8, 0).push_glyph(&[], &[]),
+        Err(WriteError::ZeroDimension {
+            width: 8,
+            height: 0
+        })
+    );
+    assert_eq!(
+        FontBuilder::new(0, 8).to_psf2(),
+        Err(WriteError::ZeroDimension {
+            width: 0,
+            height: 8
+        })
+    );
+}
+
+#[test]
+fn psf1() {
+    let font = Font::new(FONT).unwrap();
+    let data = font.to_psf1().unwrap();
+    assert_eq!(&data[..4], &[0x36, 0x04, 0x02, 12]);
+    let converted = Font::new(&data[..]).unwrap();
+    // Glyphs are padded with blank columns to 8 wide
+    assert_eq!((converted.width(), converted.height()), (8, 12));
+    assert_eq!(converted.glyph_count(), 256);
+    for ((_, a, x), (_, b, y)) in font.glyphs().zip(converted.glyphs()) {
+        for (a, b) in a.zip(b) {
+            assert_eq!(
+                a.chain([false; 2]).collect::<Vec<_>>(),
+                b.collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(
+            x.map(|x| format!("{x:?}")).collect::<Vec<_>>(),
+            y.map(|x| format!("{x:?}")).collect::<Vec<_>>()
+        );
+    }
+    // And back again, with only the Unicode table unchanged
+    let table = 32 + 256 * 12;
+    assert_eq!(converted.to_psf2().unwrap()[table..], FONT[table..]);
+
+    // Bits beyond narrower glyphs are cleared
+    let mut builder = FontBuilder::new(6, 2);
+    builder.push_glyph(&[0xff, 0x84], &['x']).unwrap();
+    let data = builder.to_psf1().unwrap();
+    assert_eq!(&data[4..6], &[0xfc, 0x84]);
+}
+
+#[test]
+fn psf1_sequences() {
+    let mut builder = FontBuilder::new(8, 2);
+    for i in 0..300u32 {
+        builder.push_glyph(&[i as u8, 0], &[]).unwrap();
+    }
+    builder.add_char(1, 'a');
+    builder.add_sequence(299, &['a', '\u{301}']);
+    let data = builder.to_psf1().unwrap();
+    assert_eq!(&data[..4], &[0x36, 0x04, 0x07, 2]);
+    assert_eq!(data.len(), 4 + 512 * 2 + 300 * 2 + 2 + 6 + 212 * 2);
+    let font = Font::new(&data[..]).unwrap();
+    assert_eq!(font.glyph_count(), 512);
+    assert_eq!(font.get_unicode('a').unwrap().data(), &[1, 0]);
+    assert_eq!(
+        font.get_sequence(&['a', '\u{301}']).unwrap().data(),
+        &[43, 0]
+    );
+    assert_eq!(font.get_index(511).unwrap().data(), &[0, 0]);
+
+    // Without mappings, there's no table
+    let data = FontBuilder::new(4, 3).to_psf1().unwrap();
+    assert_eq!(&data[..4], &[0x36, 0x04, 0x00, 3]);
+    assert_eq!(data.len(), 4 + 256 * 3);
+}
+
+#[test]
+fn psf1_errors() {
+    let mut builder = FontBuilder::new(9, 1);
+    builder.push_glyph(&[0; 2], &['a']).unwrap();
+    assert_eq!(builder.to_psf1(), Err(WriteError::TooWide { width: 9 }));
+
+    let mut builder = FontBuilder::new(8, 1);
+    for _ in 0..513 {
+        builder.push_glyph(&[0], &[]).unwrap();
+    }
+    assert_eq!(
+        builder.to_psf1(),
+        Err(WriteError::TooManyGlyphs { count: 513 })
+    );
+
+    let mut builder = FontBuilder::new(8, 1);
+    builder.push_glyph(&[0], &['\u{1f600}']).unwrap();
+    assert_eq!(
+        builder.to_psf1(),
+        Err(WriteError::Unencodable { c: '\u{1f600}' })
+    );
+    assert!(builder.to_psf2().is_ok());
+
+    assert_eq!(
+        FontBuilder::new(8, 256).to_psf1(),
+        Err(WriteError::TooLarge)
+    );
+}